@@ -0,0 +1,24 @@
+use std::fmt;
+
+// Amounts are in chips. A raise records the total the player has put in on
+// the current street, a call records only the chips added.
+#[derive(Clone, Debug, Copy, Eq, PartialEq, Hash)]
+pub enum Action {
+  Fold,
+  Check,
+  Call(u64),
+  Bet(u64),
+  Raise(u64),
+}
+
+impl fmt::Display for Action {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::Fold => write!(f, "folds"),
+      Self::Check => write!(f, "checks"),
+      Self::Call(amount) => write!(f, "calls {}", amount),
+      Self::Bet(amount) => write!(f, "bets {}", amount),
+      Self::Raise(to) => write!(f, "raises to {}", to),
+    }
+  }
+}
@@ -0,0 +1,363 @@
+// Bots that can fill a seat at a Table, and what they get to see.
+
+use crate::texture::Pairing;
+use crate::texture::Texture;
+use crate::Action;
+use crate::Card;
+use crate::Combo;
+use crate::Deal;
+use crate::Hand;
+use crate::HandClass;
+use crate::Legal;
+use crate::Rank;
+use crate::Rng;
+use crate::Street;
+use crate::Table;
+use std::cmp::Ordering;
+
+// The table from the point of view of the player due to act.
+#[derive(Clone, Debug, PartialEq)]
+pub struct View<'a> {
+  pub seat: usize,
+  pub hole: [Card; 2],
+  pub board: &'a [Card],
+  pub street: Street,
+  pub button: usize,
+  pub big_blind: u64,
+  pub pot: u64,
+  pub stacks: &'a [u64],
+  pub contributions: &'a [u64],
+  pub folded: &'a [bool],
+  pub legal: Legal,
+}
+
+impl<'a> View<'a> {
+  // None once the betting is over
+  pub fn new(table: &'a Table, hole: [Card; 2], board: &'a [Card]) -> Option<Self> {
+    Some(Self {
+      seat: table.to_act()?,
+      hole,
+      board,
+      street: table.street(),
+      button: table.button(),
+      big_blind: table.big_blind(),
+      pot: table.pot(),
+      stacks: table.stacks(),
+      contributions: table.contributions(),
+      folded: table.folded(),
+      legal: table.legal()?,
+    })
+  }
+  // players still in the hand besides this one
+  pub fn opponents(&self) -> usize {
+    self.folded.iter().filter(|f| !**f).count() - 1
+  }
+  // the share of the pot a call has to win to break even
+  pub fn pot_odds(&self) -> f64 {
+    let to_call = self.legal.to_call as f64;
+    to_call / (self.pot as f64 + to_call)
+  }
+  // the best hand so far, once there is a board
+  pub fn rank(&self) -> Option<Rank> {
+    Deal::partial(self.hole, self.board).ok().map(|d| d.rank())
+  }
+  // None if both hole cards are the same card
+  pub fn class(&self) -> Option<HandClass> {
+    Combo::try_new(self.hole[0], self.hole[1])
+      .ok()
+      .map(HandClass::from)
+  }
+  // a bet or raise of about the pot, or a call if raising isn't allowed
+  fn pot_raise(&self) -> Action {
+    let to = self.legal.current_bet + self.pot + self.legal.to_call;
+    self.legal.raise(to).unwrap_or(self.legal.call())
+  }
+  // a check when it's free, otherwise a fold
+  fn give_up(&self) -> Action {
+    if self.legal.can_check() {
+      Action::Check
+    } else {
+      Action::Fold
+    }
+  }
+}
+
+pub trait Agent {
+  fn name(&self) -> String;
+  // must be one of the actions the view's Legal allows
+  fn act(&mut self, view: &View) -> Action;
+}
+
+// Asks the agents, in seat order, for actions until the betting is over.
+// Seat i holds deck[2i] and deck[2i + 1] and the board follows the hole
+// cards.
+pub fn play_out(
+  table: &mut Table,
+  agents: &mut [Box<dyn Agent>],
+  deck: &[Card],
+) -> Result<(), &'static str> {
+  let n = agents.len();
+  while let Some(seat) = table.to_act() {
+    let board = &deck[2 * n..2 * n + table.street().board_len()];
+    let hole = [deck[2 * seat], deck[2 * seat + 1]];
+    let view = View::new(table, hole, board).unwrap();
+    let action = agents[seat].act(&view);
+    table.apply(action)?;
+  }
+  Ok(())
+}
+
+// Checks or calls every time.
+#[derive(Clone, Debug, Default)]
+pub struct CallingStation;
+
+impl Agent for CallingStation {
+  fn name(&self) -> String {
+    "Calling Station".to_string()
+  }
+  fn act(&mut self, view: &View) -> Action {
+    view.legal.call()
+  }
+}
+
+// Picks uniformly from folding, calling, the smallest raise and all in.
+#[derive(Clone, Debug)]
+pub struct RandomAgent {
+  rng: Rng,
+}
+
+impl RandomAgent {
+  pub fn new(seed: u64) -> Self {
+    Self {
+      rng: Rng::new(seed),
+    }
+  }
+}
+
+impl Agent for RandomAgent {
+  fn name(&self) -> String {
+    "Random".to_string()
+  }
+  fn act(&mut self, view: &View) -> Action {
+    let actions = view.legal.actions();
+    actions[self.rng.below(actions.len())]
+  }
+}
+
+// Plays strong starting hands, raises them, and after the flop bets two
+// pair or better, calls small bets with a pair and gives up otherwise.
+#[derive(Clone, Debug, Default)]
+pub struct TightAggressive;
+
+impl TightAggressive {
+  fn premium(class: &HandClass) -> bool {
+    let faces = (class.high() as usize, class.low() as usize);
+    match faces {
+      (h, l) if h == l => h >= 7,
+      // AK and AQ suited, counting from Two as 0
+      (12, 11) => true,
+      (12, 10) => class.is_suited(),
+      _ => false,
+    }
+  }
+  fn playable(class: &HandClass) -> bool {
+    let (high, low) = (class.high() as usize, class.low() as usize);
+    class.is_pair() || low >= 8 || (high == 12 && class.is_suited())
+  }
+  // the category the board makes on its own, which everyone shares
+  fn board_category(board: &[Card]) -> usize {
+    if let Ok(board) = <[Card; 5]>::try_from(board) {
+      return Hand::new(&board).rank().category();
+    }
+    match Texture::new(board).map(|t| t.pairing) {
+      Ok(Pairing::Paired) => 1,
+      Ok(Pairing::TwoPair) => 2,
+      Ok(Pairing::Trips) => 3,
+      Ok(Pairing::FullHouse) => 6,
+      Ok(Pairing::Quads) => 7,
+      _ => 0,
+    }
+  }
+}
+
+impl Agent for TightAggressive {
+  fn name(&self) -> String {
+    "Tight Aggressive".to_string()
+  }
+  fn act(&mut self, view: &View) -> Action {
+    let Some(rank) = view.rank() else {
+      let Some(class) = view.class() else {
+        return view.give_up();
+      };
+      return if Self::premium(&class) {
+        let to = 3 * view.legal.current_bet.max(view.big_blind);
+        view.legal.raise(to).unwrap_or(view.legal.call())
+      } else if Self::playable(&class) && view.legal.to_call <= 4 * view.big_blind {
+        view.legal.call()
+      } else {
+        view.give_up()
+      };
+    };
+    // a hand the board makes by itself is no better than a high card
+    let category = rank.category();
+    let category = if category > Self::board_category(view.board) {
+      category
+    } else {
+      0
+    };
+    match category {
+      0 => view.give_up(),
+      1 if 2 * view.legal.to_call <= view.pot => view.legal.call(),
+      1 => view.give_up(),
+      _ => view.pot_raise(),
+    }
+  }
+}
+
+// Estimates its equity against random hands by simulation, raises above a
+// threshold and otherwise calls when the pot odds are good enough.
+#[derive(Clone, Debug)]
+pub struct EquityAgent {
+  pub raise_above: f64,
+  pub trials: usize,
+  rng: Rng,
+}
+
+impl EquityAgent {
+  pub fn new(raise_above: f64, trials: usize, seed: u64) -> Self {
+    Self {
+      raise_above,
+      trials,
+      rng: Rng::new(seed),
+    }
+  }
+  // share of the pot won against the opponents holding random cards
+  pub fn equity(&mut self, hole: [Card; 2], board: &[Card], opponents: usize) -> f64 {
+    let dead: Vec<Card> = hole.iter().chain(board).copied().collect();
+    let mut deck: Vec<Card> = Card::all()
+      .into_iter()
+      .filter(|c| !dead.contains(c))
+      .collect();
+    let needed = 5 - board.len();
+    let mut share = 0.0;
+    for _ in 0..self.trials {
+      self.rng.partial_shuffle(&mut deck, needed + 2 * opponents);
+      let mut full = board.to_vec();
+      full.extend_from_slice(&deck[..needed]);
+      let mine = Deal::partial(hole, &full).unwrap().rank();
+      let mut tied = 1;
+      let mut lost = false;
+      for i in 0..opponents {
+        let theirs = [deck[needed + 2 * i], deck[needed + 2 * i + 1]];
+        match Deal::partial(theirs, &full).unwrap().rank().cmp(&mine) {
+          Ordering::Greater => lost = true,
+          Ordering::Equal => tied += 1,
+          Ordering::Less => {}
+        }
+      }
+      if !lost {
+        share += 1.0 / tied as f64;
+      }
+    }
+    share / self.trials as f64
+  }
+}
+
+impl Agent for EquityAgent {
+  fn name(&self) -> String {
+    format!("Equity {:.2}", self.raise_above)
+  }
+  fn act(&mut self, view: &View) -> Action {
+    let equity = self.equity(view.hole, view.board, view.opponents().max(1));
+    if equity >= self.raise_above {
+      view.pot_raise()
+    } else if view.legal.can_check() || equity >= view.pot_odds() {
+      view.legal.call()
+    } else {
+      Action::Fold
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // Plays one hand through to the end, dealing from a shuffled deck.
+  fn play(agents: &mut [Box<dyn Agent>], seed: u64) -> Table {
+    let mut rng = Rng::new(seed);
+    let mut deck = Card::all();
+    rng.shuffle(&mut deck);
+    let mut table = Table::new(vec![200; agents.len()], 0, 1, 2, 0).unwrap();
+    play_out(&mut table, agents, &deck).unwrap();
+    assert!(table.is_finished());
+    table
+  }
+
+  fn cards(s: &str) -> Vec<Card> {
+    s.split_whitespace().map(|c| c.parse().unwrap()).collect()
+  }
+
+  #[test]
+  fn test_agents_play_legally() {
+    for seed in 0..20 {
+      let mut agents: Vec<Box<dyn Agent>> = vec![
+        Box::new(CallingStation),
+        Box::new(RandomAgent::new(seed)),
+        Box::new(TightAggressive),
+        Box::new(EquityAgent::new(0.6, 20, seed)),
+      ];
+      let table = play(&mut agents, seed);
+      assert!(table.is_finished());
+    }
+  }
+  #[test]
+  fn test_view() {
+    let table = Table::new(vec![100, 100, 100], 0, 1, 2, 0).unwrap();
+    let hole = [cards("AH")[0], cards("KH")[0]];
+    let view = View::new(&table, hole, &[]).unwrap();
+    assert_eq!(view.seat, 0);
+    assert_eq!(view.opponents(), 2);
+    assert_eq!(view.rank(), None);
+    assert_eq!(view.class().unwrap().to_string(), "AKs");
+    let pair = View::new(&table, [hole[0]; 2], &[]).unwrap();
+    assert_eq!(pair.class(), None);
+    assert!((view.pot_odds() - 2.0 / 5.0).abs() < 1e-12);
+  }
+  #[test]
+  fn test_tight_aggressive() {
+    let table = Table::new(vec![100, 100], 0, 1, 2, 0).unwrap();
+    let mut bot = TightAggressive;
+    let aces = cards("AH AS");
+    let view = View::new(&table, [aces[0], aces[1]], &[]).unwrap();
+    assert_eq!(bot.act(&view), Action::Raise(6));
+    let junk = cards("7C 2D");
+    let view = View::new(&table, [junk[0], junk[1]], &[]).unwrap();
+    assert_eq!(bot.act(&view), Action::Fold);
+
+    // a pair only the board makes isn't worth a bet, a second pair is
+    let mut table = table;
+    table.apply(Action::Call(1)).unwrap();
+    table.apply(Action::Check).unwrap();
+    let board = cards("KD KC 7S");
+    let nothing = cards("4C 3D");
+    let view = View::new(&table, [nothing[0], nothing[1]], &board).unwrap();
+    assert_eq!(view.rank().unwrap().category(), 1);
+    assert_eq!(bot.act(&view), Action::Check);
+    let view = View::new(&table, [junk[0], junk[1]], &board).unwrap();
+    assert!(matches!(bot.act(&view), Action::Bet(_)));
+    let river = cards("KD KC 7S 7H 2S");
+    let view = View::new(&table, [nothing[0], nothing[1]], &river).unwrap();
+    assert_eq!(bot.act(&view), Action::Check);
+  }
+  #[test]
+  fn test_equity_agent() {
+    let mut bot = EquityAgent::new(0.8, 400, 1);
+    let aces = cards("AH AS");
+    let equity = bot.equity([aces[0], aces[1]], &[], 1);
+    assert!((equity - 0.85).abs() < 0.05, "{}", equity);
+    // the nuts on the river can't lose
+    let board = cards("AD AC 7S 2H 9D");
+    assert_eq!(bot.equity([aces[0], aces[1]], &board, 3), 1.0);
+  }
+}
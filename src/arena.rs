@@ -0,0 +1,304 @@
+// Self-play between agents. Every deal is played once per seat rotation, so
+// each agent gets every seat's cards in every position (for two players,
+// the same cards mirrored). Summing an agent's results over the rotations
+// cancels most of the luck of the deal, leaving the difference in play.
+//
+// Stacks are reset to the starting stack for every hand, and results are in
+// big blinds per 100 hands with a 95% confidence interval.
+
+use crate::agent;
+use crate::agent::Agent;
+use crate::Card;
+use crate::Deal;
+use crate::Permutation;
+use crate::Rng;
+use crate::Table;
+use std::fmt;
+
+pub struct Arena {
+  agents: Vec<Box<dyn Agent>>,
+  stack: u64,
+  small_blind: u64,
+  big_blind: u64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Standing {
+  pub name: String,
+  pub hands: u64,
+  pub bb_per_100: f64,
+  // half the width of the 95% confidence interval
+  pub margin: f64,
+}
+
+// Heads-up results of every agent against every other.
+#[derive(Clone, Debug, PartialEq)]
+pub struct League {
+  pub names: Vec<String>,
+  // row's bb/100 against column, None on the diagonal
+  pub results: Vec<Vec<Option<Standing>>>,
+}
+
+// Plays one hand with the agents in seat order, the button in seat 0. Seat
+// i holds deck[2i] and deck[2i + 1] and the board follows the hole cards.
+// Returns each seat's winnings, net of what it put in.
+pub fn play_hand(
+  agents: &mut [Box<dyn Agent>],
+  deck: &[Card],
+  stack: u64,
+  small_blind: u64,
+  big_blind: u64,
+) -> Result<Vec<i64>, &'static str> {
+  let n = agents.len();
+  let mut table = Table::new(vec![stack; n], 0, small_blind, big_blind, 0)?;
+  let hole = |seat: usize| [deck[2 * seat], deck[2 * seat + 1]];
+  let board = &deck[2 * n..2 * n + 5];
+  agent::play_out(&mut table, agents, deck)?;
+  let ranks: Vec<_> = (0..n)
+    .map(|seat| {
+      (!table.folded()[seat]).then(|| Deal::new(hole(seat), board.try_into().unwrap()).rank())
+    })
+    .collect();
+  let payouts = table.payouts(&ranks);
+  Ok(
+    payouts
+      .iter()
+      .zip(table.contributions())
+      .map(|(&won, &put)| won as i64 - put as i64)
+      .collect(),
+  )
+}
+
+fn standing(name: String, samples: &[f64], rotations: usize) -> Standing {
+  let count = samples.len() as f64;
+  let mean = samples.iter().sum::<f64>() / count;
+  let variance = if samples.len() > 1 {
+    samples.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / (count - 1.0)
+  } else {
+    0.0
+  };
+  // each sample is a whole rotation, so scale down to a single hand
+  let per_hand = 100.0 / rotations as f64;
+  Standing {
+    name,
+    hands: (samples.len() * rotations) as u64,
+    bb_per_100: mean * per_hand,
+    margin: 1.96 * (variance / count).sqrt() * per_hand,
+  }
+}
+
+impl Arena {
+  pub fn new(stack: u64, small_blind: u64, big_blind: u64) -> Self {
+    Self {
+      agents: Vec::new(),
+      stack,
+      small_blind,
+      big_blind,
+    }
+  }
+  pub fn add(&mut self, agent: Box<dyn Agent>) {
+    self.agents.push(agent);
+  }
+  pub fn names(&self) -> Vec<String> {
+    self.agents.iter().map(|a| a.name()).collect()
+  }
+  // Plays `deals` duplicate deals with the given agents, by index, at one
+  // table. Returns their standings in the same order.
+  pub fn play(
+    &mut self,
+    players: &[usize],
+    deals: usize,
+    seed: u64,
+  ) -> Result<Vec<Standing>, &'static str> {
+    let n = players.len();
+    if !(2..=Permutation::MAX_PLAYERS).contains(&n) {
+      return Err("A table needs between 2 and 23 players.");
+    }
+    if players.iter().any(|&p| p >= self.agents.len()) {
+      return Err("No such agent.");
+    }
+    if (1..n).any(|i| players[..i].contains(&players[i])) {
+      return Err("An agent can only take one seat.");
+    }
+    let mut rng = Rng::new(seed);
+    let mut deck = Card::all();
+    let mut samples = vec![Vec::with_capacity(deals); n];
+    // borrow the players' agents, in seat order, for the length of the match
+    let mut slots: Vec<Option<Box<dyn Agent>>> = self.agents.drain(..).map(Some).collect();
+    let mut seated: Vec<Box<dyn Agent>> =
+      players.iter().map(|&p| slots[p].take().unwrap()).collect();
+    let mut result = Ok(());
+    for _ in 0..deals {
+      rng.partial_shuffle(&mut deck, 2 * n + 5);
+      let mut totals = vec![0.0; n];
+      // after `rotation` turns player i sits in seat (i + rotation) % n, and
+      // after n turns everyone is back where they started
+      for rotation in 0..n {
+        match play_hand(
+          &mut seated,
+          &deck,
+          self.stack,
+          self.small_blind,
+          self.big_blind,
+        ) {
+          Ok(winnings) => {
+            for (p, total) in totals.iter_mut().enumerate() {
+              *total += winnings[(p + rotation) % n] as f64 / self.big_blind as f64;
+            }
+          }
+          Err(e) => result = Err(e),
+        }
+        seated.rotate_right(1);
+      }
+      if result.is_err() {
+        break;
+      }
+      for (p, total) in totals.into_iter().enumerate() {
+        samples[p].push(total);
+      }
+    }
+    for (&p, agent) in players.iter().zip(seated) {
+      slots[p] = Some(agent);
+    }
+    self.agents = slots.into_iter().map(Option::unwrap).collect();
+    result?;
+    Ok(
+      players
+        .iter()
+        .zip(samples)
+        .map(|(&p, s)| standing(self.agents[p].name(), &s, n))
+        .collect(),
+    )
+  }
+  // Every agent at one table.
+  pub fn run(&mut self, deals: usize, seed: u64) -> Result<Vec<Standing>, &'static str> {
+    let players: Vec<usize> = (0..self.agents.len()).collect();
+    self.play(&players, deals, seed)
+  }
+  // Every pair of agents heads up, each match on the same deals.
+  pub fn league(&mut self, deals: usize, seed: u64) -> Result<League, &'static str> {
+    let n = self.agents.len();
+    let mut results = vec![vec![None; n]; n];
+    let pairs: Vec<(usize, usize)> = (0..n)
+      .flat_map(|a| (a + 1..n).map(move |b| (a, b)))
+      .collect();
+    for (a, b) in pairs {
+      let [first, second]: [Standing; 2] = self.play(&[a, b], deals, seed)?.try_into().unwrap();
+      results[a][b] = Some(first);
+      results[b][a] = Some(second);
+    }
+    Ok(League {
+      names: self.names(),
+      results,
+    })
+  }
+}
+
+impl fmt::Display for Standing {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "{:<20} {:>+9.2} ± {:<8.2} bb/100 over {} hands",
+      self.name, self.bb_per_100, self.margin, self.hands
+    )
+  }
+}
+
+impl League {
+  // each agent's average bb/100 over its matches, best first
+  pub fn table(&self) -> Vec<(String, f64)> {
+    let mut rows: Vec<(String, f64)> = self
+      .names
+      .iter()
+      .zip(&self.results)
+      .map(|(name, row)| {
+        let played: Vec<f64> = row.iter().flatten().map(|s| s.bb_per_100).collect();
+        let average = played.iter().sum::<f64>() / played.len().max(1) as f64;
+        (name.clone(), average)
+      })
+      .collect();
+    rows.sort_by(|a, b| b.1.total_cmp(&a.1));
+    rows
+  }
+}
+
+impl fmt::Display for League {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{:<20}", "")?;
+    for i in 0..self.names.len() {
+      write!(f, " {:>9}", i + 1)?;
+    }
+    writeln!(f)?;
+    for (i, (name, row)) in self.names.iter().zip(&self.results).enumerate() {
+      write!(f, "{:<20}", format!("{}. {}", i + 1, name))?;
+      for result in row {
+        match result {
+          Some(s) => write!(f, " {:>+9.1}", s.bb_per_100)?,
+          None => write!(f, " {:>9}", "-")?,
+        }
+      }
+      writeln!(f)?;
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::agent::{CallingStation, RandomAgent, TightAggressive};
+
+  fn arena() -> Arena {
+    let mut arena = Arena::new(200, 1, 2);
+    arena.add(Box::new(CallingStation));
+    arena.add(Box::new(TightAggressive));
+    arena.add(Box::new(RandomAgent::new(3)));
+    arena
+  }
+
+  #[test]
+  fn test_mirrored_calling_stations_break_even() {
+    // identical strategies on mirrored cards win back exactly what they lose
+    let mut arena = Arena::new(100, 1, 2);
+    arena.add(Box::new(CallingStation));
+    arena.add(Box::new(CallingStation));
+    let standings = arena.run(200, 1).unwrap();
+    for s in &standings {
+      assert_eq!(s.bb_per_100, 0.0);
+      assert_eq!(s.hands, 400);
+    }
+  }
+  #[test]
+  fn test_run() {
+    let standings = arena().run(300, 7).unwrap();
+    assert_eq!(standings.len(), 3);
+    // chips only change hands
+    let total: f64 = standings.iter().map(|s| s.bb_per_100).sum();
+    assert!(total.abs() < 1e-9, "{}", total);
+    assert!(standings.iter().all(|s| s.margin > 0.0));
+    // deterministic for a seed
+    assert_eq!(arena().run(300, 7).unwrap(), standings);
+  }
+  #[test]
+  fn test_league() {
+    let league = arena().league(200, 11).unwrap();
+    assert!(league.results[0][0].is_none());
+    let ta_vs_random = league.results[1][2].as_ref().unwrap();
+    let random_vs_ta = league.results[2][1].as_ref().unwrap();
+    assert_eq!(ta_vs_random.bb_per_100, -random_vs_ta.bb_per_100);
+    assert!(ta_vs_random.bb_per_100 > 0.0);
+    assert_eq!(league.table().len(), 3);
+    assert_eq!(league.to_string().lines().count(), 4);
+  }
+  #[test]
+  fn test_play_errors() {
+    let mut arena = arena();
+    assert!(arena.play(&[0], 1, 0).is_err());
+    assert_eq!(
+      arena.play(&[0; 24], 1, 0),
+      Err("A table needs between 2 and 23 players.")
+    );
+    assert!(arena.play(&[0, 0], 1, 0).is_err());
+    assert!(arena.play(&[0, 5], 1, 0).is_err());
+  }
+}
@@ -0,0 +1,33 @@
+// Hold'em against three bots in the terminal.
+//
+// To run: `cargo run --bin play`, optionally followed by a seed.
+use ccps506_poker::agent::{CallingStation, EquityAgent, TightAggressive};
+use ccps506_poker::game::{Game, Seat};
+use std::io;
+use std::process;
+
+fn main() -> io::Result<()> {
+  let seed = match std::env::args().nth(1) {
+    Some(arg) => match arg.parse() {
+      Ok(seed) => seed,
+      Err(_) => {
+        eprintln!("The seed must be a number, not {}.", arg);
+        process::exit(2);
+      }
+    },
+    None => std::time::SystemTime::now()
+      .duration_since(std::time::UNIX_EPOCH)
+      .map_or(0, |d| d.as_secs()),
+  };
+  println!("No limit hold'em, blinds 1/2, 200 chips each. Type quit to leave.");
+  let mut game = Game::new(1, 2, seed, io::stdin().lock(), io::stdout());
+  game.sit("Hero", Seat::Human, 200);
+  game.sit("Station", Seat::Bot(Box::new(CallingStation)), 200);
+  game.sit("Rock", Seat::Bot(Box::new(TightAggressive)), 200);
+  game.sit(
+    "Equity",
+    Seat::Bot(Box::new(EquityAgent::new(0.65, 200, seed))),
+    200,
+  );
+  game.run()
+}
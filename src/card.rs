@@ -4,18 +4,38 @@ use std::str::FromStr;
 use crate::Face;
 use crate::Suit;
 
-#[derive(Clone, Debug, Copy, Eq, PartialEq, Ord, PartialOrd)]
+#[derive(Clone, Debug, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub struct Card {
   face: Face,
   suit: Suit,
 }
 impl Card {
+  pub fn new(face: Face, suit: Suit) -> Self {
+    Self { face, suit }
+  }
+  // the full deck, in the 1 to 52 index order
+  pub fn all() -> [Self; 52] {
+    std::array::from_fn(|i| Self::from(i as u32 + 1))
+  }
   pub fn suit(&self) -> Suit {
     self.suit
   }
   pub fn face(&self) -> Face {
     self.face
   }
+  // Cards are numbered 1 to 52 by suit, clubs, diamonds, hearts then spades,
+  // and within a suit from the Ace up to the King: 1 is AC, 13 is KC, 14 is
+  // AD and 52 is KS.
+  pub fn from_index(idx: u32) -> Result<Self, &'static str> {
+    if !(1..=52).contains(&idx) {
+      return Err("Card numbers must be from 1 to 52.");
+    }
+    Ok(idx.into())
+  }
+  pub fn index(&self) -> u32 {
+    let face = (self.face as u32 + 1) % 13;
+    self.suit as u32 * 13 + face + 1
+  }
 }
 
 impl fmt::Display for Card {
@@ -24,6 +44,7 @@ impl fmt::Display for Card {
   }
 }
 
+// panics outside 1 to 52, see from_index
 impl From<u32> for Card {
   fn from(idx: u32) -> Self {
     Self {
@@ -37,9 +58,12 @@ impl FromStr for Card {
   type Err = &'static str;
 
   fn from_str(s: &str) -> Result<Self, Self::Err> {
+    if s.len() != 2 || !s.is_char_boundary(1) {
+      return Err("Invalid card.");
+    }
     Ok(Self {
-      face: (&s[0..1]).parse().unwrap(),
-      suit: (&s[1..2]).parse().unwrap(),
+      face: s[0..1].parse()?,
+      suit: s[1..2].parse()?,
     })
   }
 }
@@ -56,11 +80,35 @@ mod tests {
     assert_eq!(card.to_string(), "TS");
   }
   #[test]
+  fn test_index() {
+    assert_eq!(Card::from_index(1).unwrap().to_string(), "AC");
+    assert_eq!(Card::from_index(13).unwrap().to_string(), "KC");
+    assert_eq!(Card::from_index(14).unwrap().to_string(), "AD");
+    assert_eq!(Card::from_index(52).unwrap().to_string(), "KS");
+    assert!(Card::from_index(0).is_err());
+    assert!(Card::from_index(53).is_err());
+    for (i, card) in Card::all().iter().enumerate() {
+      assert_eq!(card.index(), i as u32 + 1);
+    }
+  }
+  #[test]
   fn test_from_str() {
     let card: Card = "3D".parse().unwrap();
     assert_eq!(Card::from(16), card);
     let card: Card = "QH".parse().unwrap();
     assert_eq!(Card::from(38), card);
+    assert!("XH".parse::<Card>().is_err());
+    assert!("QHX".parse::<Card>().is_err());
+    assert_eq!("Qh".parse::<Card>().unwrap(), card);
+  }
+  #[test]
+  fn test_all() {
+    let mut cards = Card::all().to_vec();
+    assert_eq!(cards[0].to_string(), "AC");
+    assert_eq!(cards[51].to_string(), "KS");
+    cards.sort();
+    cards.dedup();
+    assert_eq!(cards.len(), 52);
   }
   #[test]
   fn test_ord() {
@@ -0,0 +1,760 @@
+// Counterfactual regret minimization for two player zero-sum games, with
+// Kuhn poker, Leduc hold'em and a single street river game to try it on.
+//
+// Updates alternate between the players. CFR+ floors regrets at zero and
+// weights the average strategy by iteration, which usually converges much
+// faster than vanilla CFR.
+
+use crate::Action;
+use crate::Card;
+use crate::Combo;
+use crate::Deal;
+use crate::Face;
+use crate::Range;
+use crate::Rank;
+use crate::Suit;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+#[derive(Clone, Debug, Copy, Eq, PartialEq)]
+pub enum Node {
+  Chance,
+  Terminal,
+  // player 0 or 1 to act
+  Player(usize),
+}
+
+pub trait GameTree {
+  type State: Clone;
+
+  fn root(&self) -> Self::State;
+  fn node(&self, state: &Self::State) -> Node;
+  // the states a chance node leads to, with their probabilities
+  fn chance(&self, state: &Self::State) -> Vec<(Self::State, f64)>;
+  fn actions(&self, state: &Self::State) -> Vec<Action>;
+  fn apply(&self, state: &Self::State, action: Action) -> Self::State;
+  // player 0's winnings at a terminal state, player 1 wins the opposite
+  fn utility(&self, state: &Self::State) -> f64;
+  // what the player to act knows, as a key for their strategy
+  fn info_set(&self, state: &Self::State) -> String;
+}
+
+#[derive(Clone, Debug, Copy, Eq, PartialEq)]
+pub enum Variant {
+  Vanilla,
+  Plus,
+}
+
+// short action codes for info set keys
+fn code(action: Action) -> String {
+  match action {
+    Action::Fold => "f".to_string(),
+    Action::Check => "k".to_string(),
+    Action::Call(_) => "c".to_string(),
+    Action::Bet(amount) => format!("b{}", amount),
+    Action::Raise(to) => format!("r{}", to),
+  }
+}
+
+fn history(actions: &[Action]) -> String {
+  actions.iter().map(|&a| code(a)).collect()
+}
+
+fn regret_matching(regrets: &[f64]) -> Vec<f64> {
+  let positive: f64 = regrets.iter().map(|r| r.max(0.0)).sum();
+  if positive > 0.0 {
+    regrets.iter().map(|r| r.max(0.0) / positive).collect()
+  } else {
+    vec![1.0 / regrets.len() as f64; regrets.len()]
+  }
+}
+
+fn sign(player: usize) -> f64 {
+  if player == 0 {
+    1.0
+  } else {
+    -1.0
+  }
+}
+
+// a player's states in each info set, with its depth in the tree
+type InfoSets<S> = HashMap<String, (usize, Vec<(S, f64)>)>;
+
+pub struct Cfr<G: GameTree> {
+  game: G,
+  variant: Variant,
+  iterations: usize,
+  regrets: HashMap<String, Vec<f64>>,
+  strategy_sums: HashMap<String, Vec<f64>>,
+}
+
+impl<G: GameTree> Cfr<G> {
+  pub fn new(game: G, variant: Variant) -> Self {
+    Self {
+      game,
+      variant,
+      iterations: 0,
+      regrets: HashMap::new(),
+      strategy_sums: HashMap::new(),
+    }
+  }
+  pub fn game(&self) -> &G {
+    &self.game
+  }
+  pub fn iterations(&self) -> usize {
+    self.iterations
+  }
+  // the current strategy at an info set, from the regrets so far
+  fn current(&self, info: &str, actions: usize) -> Vec<f64> {
+    match self.regrets.get(info) {
+      Some(regrets) => regret_matching(regrets),
+      None => vec![1.0 / actions as f64; actions],
+    }
+  }
+  // the average strategy at an info set, uniform if it was never reached
+  pub fn average_strategy(&self, info: &str, actions: usize) -> Vec<f64> {
+    match self.strategy_sums.get(info) {
+      Some(sums) if sums.iter().sum::<f64>() > 0.0 => {
+        let total: f64 = sums.iter().sum();
+        sums.iter().map(|s| s / total).collect()
+      }
+      _ => vec![1.0 / actions as f64; actions],
+    }
+  }
+  // every info set seen so far with its average strategy
+  pub fn strategy(&self) -> Vec<(String, Vec<f64>)> {
+    let mut infos: Vec<(String, Vec<f64>)> = self
+      .strategy_sums
+      .keys()
+      .map(|info| {
+        let n = self.strategy_sums[info].len();
+        (info.clone(), self.average_strategy(info, n))
+      })
+      .collect();
+    infos.sort_by(|a, b| a.0.cmp(&b.0));
+    infos
+  }
+
+  fn traverse(&mut self, state: &G::State, player: usize, mine: f64, theirs: f64) -> f64 {
+    match self.game.node(state) {
+      Node::Terminal => sign(player) * self.game.utility(state),
+      Node::Chance => self
+        .game
+        .chance(state)
+        .iter()
+        .map(|(next, p)| p * self.traverse(next, player, mine, theirs * p))
+        .sum(),
+      Node::Player(actor) => {
+        let info = self.game.info_set(state);
+        let actions = self.game.actions(state);
+        let strategy = self.current(&info, actions.len());
+        if actor != player {
+          return actions
+            .iter()
+            .zip(&strategy)
+            .map(|(&a, &p)| p * self.traverse(&self.game.apply(state, a), player, mine, theirs * p))
+            .sum();
+        }
+        let values: Vec<f64> = actions
+          .iter()
+          .zip(&strategy)
+          .map(|(&a, &p)| self.traverse(&self.game.apply(state, a), player, mine * p, theirs))
+          .collect();
+        let value: f64 = values.iter().zip(&strategy).map(|(v, p)| v * p).sum();
+        let plus = self.variant == Variant::Plus;
+        let regrets = self
+          .regrets
+          .entry(info.clone())
+          .or_insert_with(|| vec![0.0; actions.len()]);
+        for (regret, v) in regrets.iter_mut().zip(&values) {
+          *regret += theirs * (v - value);
+          if plus {
+            *regret = regret.max(0.0);
+          }
+        }
+        let weight = if plus { self.iterations as f64 } else { 1.0 };
+        let sums = self
+          .strategy_sums
+          .entry(info)
+          .or_insert_with(|| vec![0.0; actions.len()]);
+        for (sum, p) in sums.iter_mut().zip(&strategy) {
+          *sum += weight * mine * p;
+        }
+        value
+      }
+    }
+  }
+
+  // one update for each player in turn
+  pub fn iterate(&mut self) {
+    self.iterations += 1;
+    let root = self.game.root();
+    for player in 0..2 {
+      self.traverse(&root, player, 1.0, 1.0);
+    }
+  }
+  // runs more iterations, returning the exploitability after each
+  pub fn train(&mut self, iterations: usize) -> Vec<f64> {
+    (0..iterations)
+      .map(|_| {
+        self.iterate();
+        self.exploitability()
+      })
+      .collect()
+  }
+
+  // player 0's expected winnings when both play their average strategies
+  pub fn value(&self) -> f64 {
+    self.evaluate(&self.game.root(), None, &HashMap::new())
+  }
+  // A state's value to player 0, with `responder` playing the given choices
+  // and everyone else their average strategy.
+  fn evaluate(
+    &self,
+    state: &G::State,
+    responder: Option<usize>,
+    choices: &HashMap<String, usize>,
+  ) -> f64 {
+    match self.game.node(state) {
+      Node::Terminal => self.game.utility(state),
+      Node::Chance => self
+        .game
+        .chance(state)
+        .iter()
+        .map(|(next, p)| p * self.evaluate(next, responder, choices))
+        .sum(),
+      Node::Player(actor) => {
+        let info = self.game.info_set(state);
+        let actions = self.game.actions(state);
+        if Some(actor) == responder {
+          let next = self.game.apply(state, actions[choices[&info]]);
+          return self.evaluate(&next, responder, choices);
+        }
+        let strategy = self.average_strategy(&info, actions.len());
+        actions
+          .iter()
+          .zip(&strategy)
+          .filter(|(_, &p)| p > 0.0)
+          .map(|(&a, &p)| p * self.evaluate(&self.game.apply(state, a), responder, choices))
+          .sum()
+      }
+    }
+  }
+  // the player's states, grouped by info set, with how likely the other
+  // player and chance are to reach them
+  fn collect(
+    &self,
+    state: &G::State,
+    player: usize,
+    depth: usize,
+    reach: f64,
+    found: &mut InfoSets<G::State>,
+  ) {
+    match self.game.node(state) {
+      Node::Terminal => {}
+      Node::Chance => {
+        for (next, p) in self.game.chance(state) {
+          self.collect(&next, player, depth + 1, reach * p, found);
+        }
+      }
+      Node::Player(actor) => {
+        let info = self.game.info_set(state);
+        let actions = self.game.actions(state);
+        let strategy = if actor == player {
+          let entry = found.entry(info).or_insert((depth, Vec::new()));
+          entry.1.push((state.clone(), reach));
+          vec![1.0; actions.len()]
+        } else {
+          self.average_strategy(&info, actions.len())
+        };
+        for (&a, &p) in actions.iter().zip(&strategy) {
+          if p > 0.0 {
+            let next = self.game.apply(state, a);
+            self.collect(&next, player, depth + 1, reach * p, found);
+          }
+        }
+      }
+    }
+  }
+  // what the player wins with a best response to the other's average
+  fn best_response(&self, player: usize) -> f64 {
+    let mut found = HashMap::new();
+    self.collect(&self.game.root(), player, 0, 1.0, &mut found);
+    let mut infos: Vec<_> = found.into_iter().collect();
+    // choose deeper info sets first, so the values below are known
+    infos.sort_by(|a, b| b.1 .0.cmp(&a.1 .0).then(a.0.cmp(&b.0)));
+    let mut choices = HashMap::new();
+    for (info, (_, states)) in infos {
+      let actions = self.game.actions(&states[0].0);
+      let value = |i: usize| -> f64 {
+        states
+          .iter()
+          .map(|(state, reach)| {
+            let next = self.game.apply(state, actions[i]);
+            reach * sign(player) * self.evaluate(&next, Some(player), &choices)
+          })
+          .sum()
+      };
+      let values: Vec<f64> = (0..actions.len()).map(value).collect();
+      let best = (0..actions.len())
+        .max_by(|&a, &b| values[a].partial_cmp(&values[b]).unwrap_or(Ordering::Equal))
+        .unwrap();
+      choices.insert(info, best);
+    }
+    sign(player) * self.evaluate(&self.game.root(), Some(player), &choices)
+  }
+  // How much a best response gains against the average strategies, averaged
+  // over the two players. Zero at a Nash equilibrium.
+  pub fn exploitability(&self) -> f64 {
+    (self.best_response(0) + self.best_response(1)) / 2.0
+  }
+}
+
+// Kuhn poker: a Jack, Queen and King, an ante of 1 and a single bet of 1.
+#[derive(Clone, Debug, Copy, Default)]
+pub struct Kuhn;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KuhnState {
+  cards: Option<[Face; 2]>,
+  actions: Vec<Action>,
+}
+
+impl GameTree for Kuhn {
+  type State = KuhnState;
+
+  fn root(&self) -> KuhnState {
+    KuhnState {
+      cards: None,
+      actions: Vec::new(),
+    }
+  }
+  fn node(&self, state: &KuhnState) -> Node {
+    use Action::*;
+    match (state.cards, &state.actions[..]) {
+      (None, _) => Node::Chance,
+      (_, [Check, Check] | [_, Fold] | [.., Call(_)] | [_, _, Fold]) => Node::Terminal,
+      (_, actions) => Node::Player(actions.len() % 2),
+    }
+  }
+  fn chance(&self, _: &KuhnState) -> Vec<(KuhnState, f64)> {
+    let faces = [Face::Jack, Face::Queen, Face::King];
+    let mut deals = Vec::new();
+    for a in faces {
+      for b in faces.into_iter().filter(|&b| b != a) {
+        let state = KuhnState {
+          cards: Some([a, b]),
+          actions: Vec::new(),
+        };
+        deals.push((state, 1.0 / 6.0));
+      }
+    }
+    deals
+  }
+  fn actions(&self, state: &KuhnState) -> Vec<Action> {
+    match state.actions.last() {
+      Some(Action::Bet(_)) => vec![Action::Fold, Action::Call(1)],
+      _ => vec![Action::Check, Action::Bet(1)],
+    }
+  }
+  fn apply(&self, state: &KuhnState, action: Action) -> KuhnState {
+    let mut next = state.clone();
+    next.actions.push(action);
+    next
+  }
+  fn utility(&self, state: &KuhnState) -> f64 {
+    let [a, b] = state.cards.unwrap();
+    let showdown = if a > b { 1.0 } else { -1.0 };
+    match &state.actions[..] {
+      [Action::Check, Action::Check] => showdown,
+      [_, Action::Call(_)] | [_, _, Action::Call(_)] => 2.0 * showdown,
+      // the player who didn't fold wins the ante
+      [_, Action::Fold] => 1.0,
+      _ => -1.0,
+    }
+  }
+  fn info_set(&self, state: &KuhnState) -> String {
+    let cards = state.cards.unwrap();
+    format!(
+      "{}:{}",
+      cards[state.actions.len() % 2],
+      history(&state.actions)
+    )
+  }
+}
+
+// Leduc hold'em: two each of Jack, Queen and King, an ante of 1, a private
+// card each and one shared card. Bets are 2 before the shared card and 4
+// after, with at most a bet and a raise in each round. Pairing the shared
+// card wins, otherwise the higher card.
+#[derive(Clone, Debug, Copy, Default)]
+pub struct Leduc;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LeducState {
+  hole: Option<[Card; 2]>,
+  board: Option<Card>,
+  rounds: Vec<Vec<Action>>,
+}
+
+impl LeducState {
+  fn round(&self) -> &[Action] {
+    self.rounds.last().unwrap()
+  }
+  // what each player has put in, antes included
+  fn contributions(&self) -> [u64; 2] {
+    let mut totals = [1, 1];
+    for round in &self.rounds {
+      let mut street = [0, 0];
+      for (i, action) in round.iter().enumerate() {
+        let player = i % 2;
+        match *action {
+          Action::Bet(amount) => street[player] = amount,
+          Action::Raise(to) => street[player] = to,
+          Action::Call(amount) => street[player] += amount,
+          _ => {}
+        }
+      }
+      totals[0] += street[0];
+      totals[1] += street[1];
+    }
+    totals
+  }
+  fn round_over(&self) -> bool {
+    matches!(
+      self.round(),
+      [Action::Check, Action::Check] | [.., Action::Call(_)]
+    )
+  }
+  fn folded(&self) -> bool {
+    self.round().last() == Some(&Action::Fold)
+  }
+}
+
+fn leduc_deck() -> Vec<Card> {
+  [Face::Jack, Face::Queen, Face::King]
+    .into_iter()
+    .flat_map(|f| [Card::new(f, Suit::Hearts), Card::new(f, Suit::Spades)])
+    .collect()
+}
+
+impl GameTree for Leduc {
+  type State = LeducState;
+
+  fn root(&self) -> LeducState {
+    LeducState {
+      hole: None,
+      board: None,
+      rounds: vec![Vec::new()],
+    }
+  }
+  fn node(&self, state: &LeducState) -> Node {
+    if state.hole.is_none() {
+      return Node::Chance;
+    }
+    if state.folded() || (state.round_over() && state.board.is_some()) {
+      return Node::Terminal;
+    }
+    if state.round_over() {
+      return Node::Chance;
+    }
+    Node::Player(state.round().len() % 2)
+  }
+  fn chance(&self, state: &LeducState) -> Vec<(LeducState, f64)> {
+    let deck = leduc_deck();
+    match state.hole {
+      None => {
+        let mut deals = Vec::new();
+        for &a in &deck {
+          for &b in deck.iter().filter(|&&b| b != a) {
+            let mut next = state.clone();
+            next.hole = Some([a, b]);
+            deals.push((next, 1.0 / 30.0));
+          }
+        }
+        deals
+      }
+      Some(hole) => deck
+        .into_iter()
+        .filter(|c| !hole.contains(c))
+        .map(|card| {
+          let mut next = state.clone();
+          next.board = Some(card);
+          next.rounds.push(Vec::new());
+          (next, 0.25)
+        })
+        .collect(),
+    }
+  }
+  fn actions(&self, state: &LeducState) -> Vec<Action> {
+    let size = if state.board.is_none() { 2 } else { 4 };
+    let raises = state
+      .round()
+      .iter()
+      .filter(|a| matches!(a, Action::Bet(_) | Action::Raise(_)))
+      .count();
+    match raises {
+      0 => vec![Action::Check, Action::Bet(size)],
+      1 => vec![Action::Fold, Action::Call(size), Action::Raise(2 * size)],
+      // facing a raise, the bettor owes one more bet
+      _ => vec![Action::Fold, Action::Call(size)],
+    }
+  }
+  fn apply(&self, state: &LeducState, action: Action) -> LeducState {
+    let mut next = state.clone();
+    next.rounds.last_mut().unwrap().push(action);
+    next
+  }
+  fn utility(&self, state: &LeducState) -> f64 {
+    let [mine, theirs] = state.contributions();
+    if state.folded() {
+      let folder = (state.round().len() - 1) % 2;
+      return if folder == 0 {
+        -(mine as f64)
+      } else {
+        theirs as f64
+      };
+    }
+    let [a, b] = state.hole.unwrap().map(|c| c.face());
+    let board = state.board.unwrap().face();
+    let strength = |f: Face| (f == board, f);
+    match strength(a).cmp(&strength(b)) {
+      Ordering::Greater => theirs as f64,
+      Ordering::Less => -(mine as f64),
+      Ordering::Equal => 0.0,
+    }
+  }
+  fn info_set(&self, state: &LeducState) -> String {
+    let player = state.round().len() % 2;
+    let card = state.hole.unwrap()[player].face();
+    let rounds: Vec<String> = state.rounds.iter().map(|r| history(r)).collect();
+    match state.board {
+      Some(board) => format!("{}{}:{}", card, board.face(), rounds.join("/")),
+      None => format!("{}:{}", card, rounds.join("/")),
+    }
+  }
+}
+
+// One street of betting on a complete board between two ranges. Player 0
+// checks or bets; after a check player 1 checks or bets; a bet is called or
+// folded. Bets are fractions of the pot and capped by the stack.
+#[derive(Clone, Debug)]
+pub struct River {
+  board: [Card; 5],
+  pot: f64,
+  bets: Vec<u64>,
+  deals: Vec<(usize, usize, f64)>,
+  hands: [Vec<(Combo, Rank)>; 2],
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RiverState {
+  hands: Option<(usize, usize)>,
+  actions: Vec<Action>,
+}
+
+impl River {
+  pub fn new(
+    board: [Card; 5],
+    ranges: [&Range; 2],
+    pot: u64,
+    stack: u64,
+    sizes: &[f64],
+  ) -> Result<Self, &'static str> {
+    let hands = ranges.map(|range| {
+      range
+        .without(&board)
+        .combos()
+        .map(|(combo, _)| (combo, Deal::new(combo.cards(), board).rank()))
+        .collect::<Vec<(Combo, Rank)>>()
+    });
+    let weight = |player: usize, combo: Combo| ranges[player].weight(&combo);
+    let mut deals = Vec::new();
+    for (i, (a, _)) in hands[0].iter().enumerate() {
+      for (j, (b, _)) in hands[1].iter().enumerate() {
+        if !a.overlaps(b) {
+          deals.push((i, j, weight(0, *a) * weight(1, *b)));
+        }
+      }
+    }
+    let total: f64 = deals.iter().map(|d| d.2).sum();
+    if total <= 0.0 {
+      return Err("The ranges have no hands left to deal.");
+    }
+    for deal in deals.iter_mut() {
+      deal.2 /= total;
+    }
+    let mut bets: Vec<u64> = sizes
+      .iter()
+      .map(|f| ((f * pot as f64).round() as u64).clamp(1, stack))
+      .collect();
+    bets.sort();
+    bets.dedup();
+    Ok(Self {
+      board,
+      pot: pot as f64,
+      bets,
+      deals,
+      hands,
+    })
+  }
+  pub fn board(&self) -> [Card; 5] {
+    self.board
+  }
+  // the info set key for a combo after some actions
+  pub fn info_set_for(&self, combo: Combo, actions: &[Action]) -> String {
+    format!("{}:{}", combo, history(actions))
+  }
+}
+
+impl GameTree for River {
+  type State = RiverState;
+
+  fn root(&self) -> RiverState {
+    RiverState {
+      hands: None,
+      actions: Vec::new(),
+    }
+  }
+  fn node(&self, state: &RiverState) -> Node {
+    use Action::*;
+    match (state.hands, &state.actions[..]) {
+      (None, _) => Node::Chance,
+      (_, [Check, Check] | [.., Fold] | [.., Call(_)]) => Node::Terminal,
+      (_, actions) => Node::Player(actions.len() % 2),
+    }
+  }
+  fn chance(&self, _: &RiverState) -> Vec<(RiverState, f64)> {
+    self
+      .deals
+      .iter()
+      .map(|&(i, j, p)| {
+        let state = RiverState {
+          hands: Some((i, j)),
+          actions: Vec::new(),
+        };
+        (state, p)
+      })
+      .collect()
+  }
+  fn actions(&self, state: &RiverState) -> Vec<Action> {
+    match state.actions.last() {
+      Some(&Action::Bet(amount)) => vec![Action::Fold, Action::Call(amount)],
+      _ => std::iter::once(Action::Check)
+        .chain(self.bets.iter().map(|&b| Action::Bet(b)))
+        .collect(),
+    }
+  }
+  fn apply(&self, state: &RiverState, action: Action) -> RiverState {
+    let mut next = state.clone();
+    next.actions.push(action);
+    next
+  }
+  fn utility(&self, state: &RiverState) -> f64 {
+    let (i, j) = state.hands.unwrap();
+    let bet = state
+      .actions
+      .iter()
+      .find_map(|a| match a {
+        Action::Bet(amount) => Some(*amount as f64),
+        _ => None,
+      })
+      .unwrap_or(0.0);
+    let half = self.pot / 2.0;
+    if state.actions.last() == Some(&Action::Fold) {
+      let folder = (state.actions.len() - 1) % 2;
+      return if folder == 0 { -half } else { half };
+    }
+    match self.hands[0][i].1.cmp(&self.hands[1][j].1) {
+      Ordering::Greater => half + bet,
+      Ordering::Less => -(half + bet),
+      Ordering::Equal => 0.0,
+    }
+  }
+  fn info_set(&self, state: &RiverState) -> String {
+    let (i, j) = state.hands.unwrap();
+    let player = state.actions.len() % 2;
+    let combo = if player == 0 {
+      self.hands[0][i].0
+    } else {
+      self.hands[1][j].0
+    };
+    self.info_set_for(combo, &state.actions)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_kuhn() {
+    let mut cfr = Cfr::new(Kuhn, Variant::Plus);
+    let exploitability = cfr.train(1000);
+    assert!(exploitability[999] < 0.005, "{}", exploitability[999]);
+    assert!(exploitability[999] < exploitability[0]);
+    // the first player's value is -1/18
+    assert!((cfr.value() + 1.0 / 18.0).abs() < 0.005, "{}", cfr.value());
+    // the second player always calls with a King and never with a Jack
+    assert!(cfr.average_strategy("K:b1", 2)[1] > 0.99);
+    assert!(cfr.average_strategy("J:b1", 2)[1] < 0.01);
+  }
+  #[test]
+  fn test_vanilla_kuhn() {
+    let mut cfr = Cfr::new(Kuhn, Variant::Vanilla);
+    let exploitability = cfr.train(300);
+    assert!(exploitability[299] < 0.02, "{}", exploitability[299]);
+  }
+  #[test]
+  fn test_leduc() {
+    let mut cfr = Cfr::new(Leduc, Variant::Plus);
+    let exploitability = cfr.train(30);
+    assert!(
+      exploitability[29] < exploitability[0] / 3.0,
+      "{:?}",
+      exploitability
+    );
+    // 3 private faces at 6 decision points before the board, and with 3
+    // board faces after each of the 5 ways to reach it
+    assert_eq!(cfr.strategy().len(), 288);
+  }
+  #[test]
+  fn test_river() {
+    let board: Vec<Card> = "2C 7D 9H JS KD"
+      .split_whitespace()
+      .map(|c| c.parse().unwrap())
+      .collect();
+    // nut sets and air against a bluff catcher
+    let hero: Range = "KK, 43s".parse().unwrap();
+    let villain: Range = "JJ".parse().unwrap();
+    let game = River::new(
+      board[..].try_into().unwrap(),
+      [&hero, &villain],
+      10,
+      100,
+      &[0.5, 1.0],
+    )
+    .unwrap();
+    let mut cfr = Cfr::new(game, Variant::Plus);
+    let exploitability = cfr.train(200);
+    // within 1% of the pot
+    assert!(exploitability[199] < 0.1, "{}", exploitability[199]);
+    assert!(exploitability[199] < exploitability[0]);
+    // the sets always bet the pot, and the air never calls
+    let set = hero
+      .without(&board)
+      .combos()
+      .find(|(c, _)| c.is_pair())
+      .unwrap()
+      .0;
+    let key = cfr.game().info_set_for(set, &[]);
+    assert!(cfr.average_strategy(&key, 3)[2] > 0.99);
+    let air: Combo = "4H3H".parse().unwrap();
+    let key = cfr
+      .game()
+      .info_set_for(air, &[Action::Check, Action::Bet(5)]);
+    assert!(cfr.average_strategy(&key, 2)[1] < 0.01);
+    let empty = Range::new();
+    assert!(River::new(cfr.game().board(), [&hero, &empty], 10, 100, &[1.0]).is_err());
+  }
+}
@@ -0,0 +1,508 @@
+// The command line tool. `run` takes the arguments after the program name
+// and returns what to print, so main only has to print it and pick an exit
+// status.
+//
+//   eval "AH KH QH JH TH"              rank five to seven cards
+//   best "AS 3S" "9S TS JS QS KS"      best hand from hole and board cards
+//   compare "AS AD" "KS KD" [--board "..."]
+//                                      which of two hands wins
+//   equity AhKh QsQd [--board "..."] [--trials N] [--seed N]
+//                                      all-in equity of two hands or ranges
+//   range "TT+, AKs"                   combos and grid of a range
+//   grade <file> [--junit <path>]      run permutation test cases
+//
+// Any command takes --json for machine readable output.
+
+use crate::equity;
+use crate::equity::Method;
+use crate::grade;
+use crate::poker;
+use crate::Card;
+use crate::Deal;
+use crate::Grid;
+use crate::Hand;
+use crate::Range;
+use std::cmp::Ordering;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+pub const USAGE: &str = "\
+Usage: ccps506_poker <command> [--json]
+  eval <cards>                        rank five to seven cards
+  best <hole> <board>                 best five card hand
+  compare <a> <b> [--board <cards>]   compare two hands
+  equity <a> <b> [--board <cards>] [--trials N] [--seed N]
+  range <range>                       list a range as a grid
+  grade <file> [--junit <path>]       run permutation test cases";
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Error {
+  // the command line itself is wrong
+  Usage(String),
+  // the cards, range or file given are
+  Invalid(String),
+}
+
+impl Error {
+  pub fn exit_code(&self) -> i32 {
+    match self {
+      Self::Usage(_) => 2,
+      Self::Invalid(_) => 1,
+    }
+  }
+}
+
+impl fmt::Display for Error {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::Usage(message) => write!(f, "{}\n\n{}", message, USAGE),
+      Self::Invalid(message) => write!(f, "{}", message),
+    }
+  }
+}
+
+impl From<&'static str> for Error {
+  fn from(message: &'static str) -> Self {
+    Self::Invalid(message.to_string())
+  }
+}
+
+// Positional arguments and --options, with --json pulled out.
+struct Args {
+  json: bool,
+  positional: Vec<String>,
+  options: Vec<(String, String)>,
+}
+
+impl Args {
+  fn parse(args: &[String]) -> Result<Self, Error> {
+    let mut parsed = Self {
+      json: false,
+      positional: Vec::new(),
+      options: Vec::new(),
+    };
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+      match arg.strip_prefix("--") {
+        Some("json") => parsed.json = true,
+        Some(name) => {
+          let value = args
+            .next()
+            .ok_or_else(|| Error::Usage(format!("--{} needs a value.", name)))?;
+          parsed.options.push((name.to_string(), value.clone()));
+        }
+        None => parsed.positional.push(arg.clone()),
+      }
+    }
+    Ok(parsed)
+  }
+  fn option(&self, name: &str) -> Option<&str> {
+    self
+      .options
+      .iter()
+      .find(|(n, _)| n == name)
+      .map(|(_, v)| v.as_str())
+  }
+  fn number(&self, name: &str) -> Result<Option<u64>, Error> {
+    self
+      .option(name)
+      .map(|v| {
+        v.parse()
+          .map_err(|_| Error::Usage(format!("--{} must be a number.", name)))
+      })
+      .transpose()
+  }
+  // exactly n positional arguments after the command
+  fn expect(&self, n: usize, allowed: &[&str]) -> Result<(), Error> {
+    if self.positional.len() != n + 1 {
+      return Err(Error::Usage(format!(
+        "{} takes {} argument{}.",
+        self.positional[0],
+        n,
+        if n == 1 { "" } else { "s" }
+      )));
+    }
+    match self
+      .options
+      .iter()
+      .find(|(n, _)| !allowed.contains(&n.as_str()))
+    {
+      Some((name, _)) => Err(Error::Usage(format!("Unknown option --{}.", name))),
+      None => Ok(()),
+    }
+  }
+}
+
+// Cards written with or without spaces or commas: "AH KH", "AhKh".
+pub fn parse_cards(s: &str) -> Result<Vec<Card>, &'static str> {
+  let compact: String = s
+    .chars()
+    .filter(|c| !c.is_whitespace() && *c != ',')
+    .collect();
+  if compact.is_empty() || !compact.len().is_multiple_of(2) || !compact.is_ascii() {
+    return Err("Invalid card.");
+  }
+  let cards: Vec<Card> = (0..compact.len())
+    .step_by(2)
+    .map(|i| compact[i..i + 2].parse())
+    .collect::<Result<_, _>>()?;
+  if (1..cards.len()).any(|i| cards[..i].contains(&cards[i])) {
+    return Err("A card is repeated.");
+  }
+  Ok(cards)
+}
+
+fn parse_hole(s: &str) -> Result<[Card; 2], &'static str> {
+  parse_cards(s)?
+    .try_into()
+    .map_err(|_| "Hole cards must be two cards.")
+}
+
+// the best five of five to seven cards
+fn best_of(cards: &[Card]) -> Result<Hand, &'static str> {
+  if !(5..=7).contains(&cards.len()) {
+    return Err("A hand needs five to seven cards.");
+  }
+  Ok(Deal::partial([cards[0], cards[1]], &cards[2..])?.best_hand())
+}
+
+fn json_string(s: &str) -> String {
+  let mut out = String::from("\"");
+  for c in s.chars() {
+    match c {
+      '"' => out.push_str("\\\""),
+      '\\' => out.push_str("\\\\"),
+      '\n' => out.push_str("\\n"),
+      c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+      c => out.push(c),
+    }
+  }
+  out.push('"');
+  out
+}
+
+fn json_cards(cards: &[Card]) -> String {
+  let cards: Vec<String> = cards.iter().map(|c| json_string(&c.to_string())).collect();
+  format!("[{}]", cards.join(","))
+}
+
+fn joined(cards: &[Card]) -> String {
+  let cards: Vec<String> = cards.iter().map(|c| c.to_string()).collect();
+  cards.join(" ")
+}
+
+fn describe(hand: &Hand, json: bool) -> String {
+  let rank = hand.rank();
+  if json {
+    format!(
+      "{{\"hand\":{},\"made\":{},\"rank\":{},\"category\":{}}}",
+      json_cards(&hand.ordered()),
+      json_cards(&hand.made()),
+      json_string(&rank.to_string()),
+      rank.category()
+    )
+  } else {
+    format!("{}  {}", joined(&hand.ordered()), rank)
+  }
+}
+
+fn eval(args: &Args) -> Result<String, Error> {
+  args.expect(1, &[])?;
+  let hand = best_of(&parse_cards(&args.positional[1])?)?;
+  Ok(describe(&hand, args.json))
+}
+
+fn best(args: &Args) -> Result<String, Error> {
+  args.expect(2, &[])?;
+  let hole = parse_hole(&args.positional[1])?;
+  let board = parse_cards(&args.positional[2])?;
+  Ok(describe(
+    &Deal::partial(hole, &board)?.best_hand(),
+    args.json,
+  ))
+}
+
+fn compare(args: &Args) -> Result<String, Error> {
+  args.expect(2, &["board"])?;
+  let (a, b) = match args.option("board") {
+    Some(board) => {
+      let board = parse_cards(board)?;
+      let a = Deal::partial(parse_hole(&args.positional[1])?, &board)?;
+      let b = Deal::partial(parse_hole(&args.positional[2])?, &board)?;
+      if a.hole().iter().any(|c| b.hole().contains(c)) {
+        return Err("Hands share a card.".into());
+      }
+      (a.best_hand(), b.best_hand())
+    }
+    None => (
+      best_of(&parse_cards(&args.positional[1])?)?,
+      best_of(&parse_cards(&args.positional[2])?)?,
+    ),
+  };
+  let winner = match a.cmp(&b) {
+    Ordering::Greater => "first",
+    Ordering::Less => "second",
+    Ordering::Equal => "tie",
+  };
+  if args.json {
+    Ok(format!(
+      "{{\"winner\":{},\"first\":{},\"second\":{}}}",
+      json_string(winner),
+      describe(&a, true),
+      describe(&b, true)
+    ))
+  } else {
+    let verdict = match winner {
+      "tie" => "Tie".to_string(),
+      w => format!("The {} hand wins", w),
+    };
+    Ok(format!(
+      "{}\n{}\n{}",
+      describe(&a, false),
+      describe(&b, false),
+      verdict
+    ))
+  }
+}
+
+fn equity(args: &Args) -> Result<String, Error> {
+  args.expect(2, &["board", "trials", "seed"])?;
+  let hero: Range = args.positional[1].parse()?;
+  let villain: Range = args.positional[2].parse()?;
+  let board = match args.option("board") {
+    Some(board) => parse_cards(board)?,
+    None => Vec::new(),
+  };
+  let trials = args.number("trials")?;
+  if trials == Some(0) {
+    return Err(Error::Usage("--trials must be at least 1.".to_string()));
+  }
+  let seed = args.number("seed")?.unwrap_or(1);
+  // every runout is quick enough once there's a flop
+  let method = match trials {
+    None if board.len() >= 3 => Method::Exhaustive,
+    trials => Method::MonteCarlo {
+      trials: trials.unwrap_or(100_000) as usize,
+      seed,
+    },
+  };
+  let result = equity::range_vs_range(&hero, &villain, &board, method)?.equity;
+  if args.json {
+    Ok(format!(
+      "{{\"board\":{},\"equity\":[{},{}],\"win\":{},\"tie\":{},\"exact\":{}}}",
+      json_cards(&board),
+      result.equity(),
+      1.0 - result.equity(),
+      result.win_rate(),
+      result.tie_rate(),
+      method == Method::Exhaustive
+    ))
+  } else {
+    Ok(format!(
+      "{:<20} {:>6.2}%\n{:<20} {:>6.2}%\nties {:.2}%",
+      args.positional[1],
+      100.0 * result.equity(),
+      args.positional[2],
+      100.0 * (1.0 - result.equity()),
+      100.0 * result.tie_rate()
+    ))
+  }
+}
+
+fn range(args: &Args) -> Result<String, Error> {
+  args.expect(1, &[])?;
+  let range: Range = args.positional[1].parse()?;
+  let grid = Grid::from(&range);
+  if args.json {
+    Ok(format!(
+      "{{\"range\":{},\"combos\":{},\"coverage\":{}}}",
+      json_string(&range.to_string()),
+      range.total_weight(),
+      grid.coverage()
+    ))
+  } else {
+    Ok(format!(
+      "{}\n{} combos, {:.1}% of hands\n{}",
+      range,
+      range.total_weight(),
+      100.0 * grid.coverage(),
+      grid.render(false)
+    ))
+  }
+}
+
+// Grades the crate's own solver against a case file, see the grade module
+// for the format. --junit also writes the results as JUnit XML.
+fn grade(args: &Args) -> Result<String, Error> {
+  args.expect(1, &["junit"])?;
+  let cases = grade::load(Path::new(&args.positional[1])).map_err(Error::Invalid)?;
+  let report = grade::grade(&cases, poker::deal);
+  if let Some(path) = args.option("junit") {
+    fs::write(path, report.junit(&args.positional[1]))
+      .map_err(|_| Error::Invalid(format!("Could not write {}.", path)))?;
+  }
+  if args.json {
+    let cases: Vec<String> = report
+      .outcomes
+      .iter()
+      .map(|o| {
+        let strings = |cards: &[String]| {
+          let cards: Vec<String> = cards.iter().map(|s| json_string(s)).collect();
+          format!("[{}]", cards.join(","))
+        };
+        format!(
+          "{{\"name\":{},\"perm\":{:?},\"returned\":{},\"missing\":{},\"extra\":{},\"score\":{}}}",
+          json_string(&o.case.name),
+          o.case.perm,
+          strings(&o.returned),
+          strings(&o.missing),
+          strings(&o.extra),
+          o.score
+        )
+      })
+      .collect();
+    Ok(format!(
+      "{{\"cases\":[{}],\"score\":{},\"failures\":{}}}",
+      cases.join(","),
+      report.total(),
+      report.failures()
+    ))
+  } else {
+    Ok(report.to_string())
+  }
+}
+
+pub fn run(args: &[String]) -> Result<String, Error> {
+  let args = Args::parse(args)?;
+  match args.positional.first().map(|s| s.as_str()) {
+    Some("eval") => eval(&args),
+    Some("best") => best(&args),
+    Some("compare") => compare(&args),
+    Some("equity") => equity(&args),
+    Some("range") => range(&args),
+    Some("grade") => grade(&args),
+    Some(command) => Err(Error::Usage(format!("Unknown command {}.", command))),
+    None => Err(Error::Usage("No command given.".to_string())),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn run_str(args: &str) -> Result<String, Error> {
+    // single quotes group words into one argument
+    let args: Vec<String> = args
+      .split('\'')
+      .enumerate()
+      .flat_map(|(i, part)| {
+        if i % 2 == 1 {
+          vec![part.to_string()]
+        } else {
+          part.split_whitespace().map(|s| s.to_string()).collect()
+        }
+      })
+      .collect();
+    run(&args)
+  }
+
+  #[test]
+  fn test_parse_cards() {
+    assert_eq!(parse_cards("AhKh"), parse_cards("AH, KH"));
+    assert_eq!(parse_cards("AH KH").unwrap().len(), 2);
+    assert!(parse_cards("AH AH").is_err());
+    assert!(parse_cards("AH K").is_err());
+    assert!(parse_cards("").is_err());
+  }
+  #[test]
+  fn test_eval_and_best() {
+    assert_eq!(
+      run_str("eval 'AH KH QH JH TH'").unwrap(),
+      "AH KH QH JH TH  Royal Flush"
+    );
+    let json = run_str("eval 'AH KH QH JH TH 2C 2D' --json").unwrap();
+    assert!(json.contains("\"category\":9"), "{}", json);
+    let best = run_str("best 'AS 3S' '9S TS JS QS KS'").unwrap();
+    assert!(best.starts_with("AS KS QS JS TS"), "{}", best);
+    assert_eq!(run_str("eval 'AH KH'").unwrap_err().exit_code(), 1);
+    assert_eq!(run_str("eval").unwrap_err().exit_code(), 2);
+  }
+  #[test]
+  fn test_compare() {
+    let out = run_str("compare 'AS AD' 'KS KD' --board '2C 7D 9H JS 3C'").unwrap();
+    assert!(out.ends_with("The first hand wins"), "{}", out);
+    let out = run_str("compare '2C 3C 4C 5C 6C' '2D 3D 4D 5D 6D' --json").unwrap();
+    assert!(out.starts_with("{\"winner\":\"tie\""), "{}", out);
+    assert!(run_str("compare 'AS AD' 'AS KD' --board '2C 7D 9H'").is_err());
+  }
+  #[test]
+  fn test_equity() {
+    let out = run_str("equity AhKh QsQd --board 'QH 7H 2C' --json").unwrap();
+    assert!(out.contains("\"exact\":true"), "{}", out);
+    let out = run_str("equity AA KK --trials 2000").unwrap();
+    assert!(out.contains('%'), "{}", out);
+    assert_eq!(
+      run_str("equity AA KK --trials lots")
+        .unwrap_err()
+        .exit_code(),
+      2
+    );
+    assert_eq!(
+      run_str("equity AA KK --trials 0").unwrap_err().exit_code(),
+      2
+    );
+    assert_eq!(
+      run_str("equity AA KK --speed 3").unwrap_err().exit_code(),
+      2
+    );
+    assert_eq!(run_str("equity AA ZZ").unwrap_err().exit_code(), 1);
+  }
+  #[test]
+  fn test_range() {
+    let out = run_str("range 'QQ+' --json").unwrap();
+    assert!(out.contains("\"combos\":18"), "{}", out);
+    assert_eq!(run_str("range 'QQ+'").unwrap().lines().count(), 15);
+    assert!(run_str("range ''").unwrap().contains("\n0 combos"));
+  }
+  #[test]
+  fn test_grade() {
+    let dir = std::env::temp_dir();
+    let path = dir.join(format!("cases-{}.txt", std::process::id()));
+    let junit = dir.join(format!("cases-{}.xml", std::process::id()));
+    let cases = "40 41 42 43 48 49 50 51 52: 10S 11S 12S 13S 1S # Royal\n\
+                 9 8 7 6 5 4 3 2 1: 1C\n";
+    fs::write(&path, cases).unwrap();
+    let arg = |p: &Path| p.to_str().unwrap().to_string();
+    let args = [
+      "grade".to_string(),
+      arg(&path),
+      "--junit".to_string(),
+      arg(&junit),
+    ];
+    let out = run(&args).unwrap();
+    assert!(out.starts_with("Test 1 FULL MARKS"), "{}", out);
+    assert!(out.contains("Test 2 DISCREPANCY"), "{}", out);
+    assert!(fs::read_to_string(&junit)
+      .unwrap()
+      .contains("failures=\"1\""));
+    let json = run(&["grade".to_string(), arg(&path), "--json".to_string()]).unwrap();
+    assert!(json.ends_with("\"score\":1,\"failures\":1}"), "{}", json);
+    fs::write(&path, "1 2 3\n").unwrap();
+    assert_eq!(
+      run(&["grade".to_string(), arg(&path)])
+        .unwrap_err()
+        .exit_code(),
+      1
+    );
+    fs::remove_file(&path).unwrap();
+    fs::remove_file(&junit).unwrap();
+    assert_eq!(run_str("grade /no/such/file").unwrap_err().exit_code(), 1);
+  }
+  #[test]
+  fn test_usage() {
+    assert_eq!(run(&[]).unwrap_err().exit_code(), 2);
+    assert_eq!(run_str("shuffle").unwrap_err().exit_code(), 2);
+    assert_eq!(run_str("eval --board").unwrap_err().exit_code(), 2);
+  }
+}
@@ -0,0 +1,565 @@
+// A compact binary encoding for storing simulated hands in bulk.
+//
+// A card is one byte, its Card::index less one, so 0 is AC and 51 is KS.
+// A deal is the number of community cards followed by its two to seven
+// cards packed into six bits each (six bytes for a river). Numbers are
+// LEB128 varints, signed ones zigzagged first, and an action is a tag byte
+// with a varint amount for calls, bets and raises. A list is its length and
+// then its items.
+//
+// Files start with "PKRB" and a u16 version, little endian, and hold blocks
+// of records: the record count and byte length as varints, the records, and
+// a CRC-32 of the records. Blocks let a reader check a file as it streams
+// it without paying four bytes for every small record.
+
+use crate::Action;
+use crate::Card;
+use crate::Deal;
+use crate::Street;
+use std::io;
+use std::io::Read;
+use std::io::Write;
+use std::marker::PhantomData;
+
+const MAGIC: &[u8; 4] = b"PKRB";
+// version 1 wrote cards as suit * 13 + face, with faces Two to Ace
+pub const VERSION: u16 = 2;
+// records are flushed once a block reaches this many bytes
+const BLOCK: usize = 1 << 16;
+
+pub trait Encode {
+  fn encode(&self, out: &mut Vec<u8>);
+}
+
+// Decoding reads from the front of the slice and advances it.
+pub trait Decode: Sized {
+  fn decode(input: &mut &[u8]) -> Result<Self, &'static str>;
+}
+
+fn byte(input: &mut &[u8]) -> Result<u8, &'static str> {
+  let (&first, rest) = input.split_first().ok_or("Unexpected end of data.")?;
+  *input = rest;
+  Ok(first)
+}
+
+pub fn card_byte(card: Card) -> u8 {
+  (card.index() - 1) as u8
+}
+
+pub fn card_from_byte(byte: u8) -> Result<Card, &'static str> {
+  Card::from_index(byte as u32 + 1).map_err(|_| "Invalid card byte.")
+}
+
+pub fn write_varint(mut value: u64, out: &mut Vec<u8>) {
+  while value >= 0x80 {
+    out.push(value as u8 | 0x80);
+    value >>= 7;
+  }
+  out.push(value as u8);
+}
+
+pub fn read_varint(input: &mut &[u8]) -> Result<u64, &'static str> {
+  let mut value = 0u64;
+  for shift in (0..64).step_by(7) {
+    let b = byte(input)?;
+    let bits = (b & 0x7f) as u64;
+    if shift == 63 && bits > 1 {
+      return Err("Varint is too long.");
+    }
+    value |= bits << shift;
+    if b & 0x80 == 0 {
+      return Ok(value);
+    }
+  }
+  Err("Varint is too long.")
+}
+
+impl Encode for u64 {
+  fn encode(&self, out: &mut Vec<u8>) {
+    write_varint(*self, out);
+  }
+}
+
+impl Decode for u64 {
+  fn decode(input: &mut &[u8]) -> Result<Self, &'static str> {
+    read_varint(input)
+  }
+}
+
+impl Encode for i64 {
+  fn encode(&self, out: &mut Vec<u8>) {
+    write_varint(((self << 1) ^ (self >> 63)) as u64, out);
+  }
+}
+
+impl Decode for i64 {
+  fn decode(input: &mut &[u8]) -> Result<Self, &'static str> {
+    let value = read_varint(input)?;
+    Ok((value >> 1) as i64 ^ -((value & 1) as i64))
+  }
+}
+
+impl Encode for Card {
+  fn encode(&self, out: &mut Vec<u8>) {
+    out.push(card_byte(*self));
+  }
+}
+
+impl Decode for Card {
+  fn decode(input: &mut &[u8]) -> Result<Self, &'static str> {
+    card_from_byte(byte(input)?)
+  }
+}
+
+impl Encode for Street {
+  fn encode(&self, out: &mut Vec<u8>) {
+    out.push(*self as u8);
+  }
+}
+
+impl Decode for Street {
+  fn decode(input: &mut &[u8]) -> Result<Self, &'static str> {
+    Street::all()
+      .get(byte(input)? as usize)
+      .copied()
+      .ok_or("Invalid street.")
+  }
+}
+
+impl Encode for Deal {
+  fn encode(&self, out: &mut Vec<u8>) {
+    let community = self.community();
+    out.push(community.len() as u8);
+    let mut bits = 0u64;
+    for (i, &card) in self.hole().iter().chain(community).enumerate() {
+      bits |= (card_byte(card) as u64) << (6 * i);
+    }
+    let bytes = (6 * (2 + community.len())).div_ceil(8);
+    out.extend_from_slice(&bits.to_le_bytes()[..bytes]);
+  }
+}
+
+impl Decode for Deal {
+  fn decode(input: &mut &[u8]) -> Result<Self, &'static str> {
+    let dealt = byte(input)? as usize;
+    if !(3..=5).contains(&dealt) {
+      return Err("A deal needs three to five community cards.");
+    }
+    let bytes = (6 * (2 + dealt)).div_ceil(8);
+    if input.len() < bytes {
+      return Err("Unexpected end of data.");
+    }
+    let mut packed = [0u8; 8];
+    packed[..bytes].copy_from_slice(&input[..bytes]);
+    *input = &input[bytes..];
+    let bits = u64::from_le_bytes(packed);
+    let cards: Vec<Card> = (0..2 + dealt)
+      .map(|i| card_from_byte((bits >> (6 * i)) as u8 & 0x3f))
+      .collect::<Result<_, _>>()?;
+    Deal::partial([cards[0], cards[1]], &cards[2..])
+  }
+}
+
+impl Encode for Action {
+  fn encode(&self, out: &mut Vec<u8>) {
+    let (tag, amount) = match *self {
+      Action::Fold => (0, None),
+      Action::Check => (1, None),
+      Action::Call(amount) => (2, Some(amount)),
+      Action::Bet(amount) => (3, Some(amount)),
+      Action::Raise(to) => (4, Some(to)),
+    };
+    out.push(tag);
+    if let Some(amount) = amount {
+      write_varint(amount, out);
+    }
+  }
+}
+
+impl Decode for Action {
+  fn decode(input: &mut &[u8]) -> Result<Self, &'static str> {
+    Ok(match byte(input)? {
+      0 => Action::Fold,
+      1 => Action::Check,
+      2 => Action::Call(read_varint(input)?),
+      3 => Action::Bet(read_varint(input)?),
+      4 => Action::Raise(read_varint(input)?),
+      _ => return Err("Invalid action tag."),
+    })
+  }
+}
+
+impl<T: Encode> Encode for [T] {
+  fn encode(&self, out: &mut Vec<u8>) {
+    write_varint(self.len() as u64, out);
+    for item in self {
+      item.encode(out);
+    }
+  }
+}
+
+impl<T: Encode> Encode for Vec<T> {
+  fn encode(&self, out: &mut Vec<u8>) {
+    self.as_slice().encode(out);
+  }
+}
+
+impl<T: Decode> Decode for Vec<T> {
+  fn decode(input: &mut &[u8]) -> Result<Self, &'static str> {
+    let len = read_varint(input)? as usize;
+    // every item takes at least a byte, so a longer list is corrupt
+    if len > input.len() {
+      return Err("List is longer than the data.");
+    }
+    (0..len).map(|_| T::decode(input)).collect()
+  }
+}
+
+impl<A: Encode, B: Encode> Encode for (A, B) {
+  fn encode(&self, out: &mut Vec<u8>) {
+    self.0.encode(out);
+    self.1.encode(out);
+  }
+}
+
+impl<A: Decode, B: Decode> Decode for (A, B) {
+  fn decode(input: &mut &[u8]) -> Result<Self, &'static str> {
+    Ok((A::decode(input)?, B::decode(input)?))
+  }
+}
+
+// Encodes a value on its own.
+pub fn to_bytes<T: Encode + ?Sized>(value: &T) -> Vec<u8> {
+  let mut out = Vec::new();
+  value.encode(&mut out);
+  out
+}
+
+// Decodes a value that fills all of bytes.
+pub fn from_bytes<T: Decode>(mut bytes: &[u8]) -> Result<T, &'static str> {
+  let value = T::decode(&mut bytes)?;
+  if !bytes.is_empty() {
+    return Err("Trailing bytes after the value.");
+  }
+  Ok(value)
+}
+
+const fn crc_table() -> [u32; 256] {
+  let mut table = [0u32; 256];
+  let mut i = 0;
+  while i < 256 {
+    let mut crc = i as u32;
+    let mut bit = 0;
+    while bit < 8 {
+      crc = if crc & 1 == 1 {
+        (crc >> 1) ^ 0xEDB8_8320
+      } else {
+        crc >> 1
+      };
+      bit += 1;
+    }
+    table[i] = crc;
+    i += 1;
+  }
+  table
+}
+
+const CRC_TABLE: [u32; 256] = crc_table();
+
+// CRC-32 as used by zip and PNG
+pub fn crc32(bytes: &[u8]) -> u32 {
+  !bytes.iter().fold(!0u32, |crc, &b| {
+    CRC_TABLE[((crc ^ b as u32) & 0xff) as usize] ^ (crc >> 8)
+  })
+}
+
+// Writes records of one type to a file, a block at a time. Call finish to
+// write the last block.
+pub struct Writer<W: Write, T: Encode> {
+  inner: W,
+  block: Vec<u8>,
+  count: u64,
+  kind: PhantomData<T>,
+}
+
+impl<W: Write, T: Encode> Writer<W, T> {
+  pub fn new(mut inner: W) -> io::Result<Self> {
+    inner.write_all(MAGIC)?;
+    inner.write_all(&VERSION.to_le_bytes())?;
+    Ok(Self {
+      inner,
+      block: Vec::new(),
+      count: 0,
+      kind: PhantomData,
+    })
+  }
+  pub fn write(&mut self, record: &T) -> io::Result<()> {
+    record.encode(&mut self.block);
+    self.count += 1;
+    if self.block.len() >= BLOCK {
+      self.flush_block()?;
+    }
+    Ok(())
+  }
+  fn flush_block(&mut self) -> io::Result<()> {
+    if self.count == 0 {
+      return Ok(());
+    }
+    let mut header = Vec::new();
+    write_varint(self.count, &mut header);
+    write_varint(self.block.len() as u64, &mut header);
+    self.inner.write_all(&header)?;
+    self.inner.write_all(&self.block)?;
+    self.inner.write_all(&crc32(&self.block).to_le_bytes())?;
+    self.block.clear();
+    self.count = 0;
+    Ok(())
+  }
+  pub fn finish(mut self) -> io::Result<W> {
+    self.flush_block()?;
+    self.inner.flush()?;
+    Ok(self.inner)
+  }
+}
+
+// Reads back the records a Writer wrote, checking each block's checksum.
+pub struct Reader<R: Read, T: Decode> {
+  inner: R,
+  block: Vec<u8>,
+  position: usize,
+  remaining: u64,
+  failed: bool,
+  kind: PhantomData<T>,
+}
+
+impl<R: Read, T: Decode> Reader<R, T> {
+  pub fn new(mut inner: R) -> Result<Self, &'static str> {
+    let mut header = [0u8; 6];
+    inner
+      .read_exact(&mut header)
+      .map_err(|_| "Not a hand record file.")?;
+    if &header[..4] != MAGIC {
+      return Err("Not a hand record file.");
+    }
+    if u16::from_le_bytes([header[4], header[5]]) != VERSION {
+      return Err("Unsupported hand record version.");
+    }
+    Ok(Self {
+      inner,
+      block: Vec::new(),
+      position: 0,
+      remaining: 0,
+      failed: false,
+      kind: PhantomData,
+    })
+  }
+  fn read_varint(&mut self) -> Result<Option<u64>, &'static str> {
+    let mut bytes = Vec::new();
+    loop {
+      let mut b = [0u8];
+      match self.inner.read(&mut b) {
+        Ok(0) if bytes.is_empty() => return Ok(None),
+        Ok(0) => return Err("Unexpected end of file."),
+        Ok(_) => bytes.push(b[0]),
+        Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+        Err(_) => return Err("Could not read hand records."),
+      }
+      if b[0] & 0x80 == 0 || bytes.len() == 10 {
+        return read_varint(&mut bytes.as_slice()).map(Some);
+      }
+    }
+  }
+  // Loads the next block. False at the end of the file.
+  fn next_block(&mut self) -> Result<bool, &'static str> {
+    let Some(count) = self.read_varint()? else {
+      return Ok(false);
+    };
+    let len = self.read_varint()?.ok_or("Unexpected end of file.")? as usize;
+    // far past what a writer produces, so a corrupt length fails cleanly
+    if count == 0 || len > 1 << 28 {
+      return Err("Corrupt block header.");
+    }
+    self.block.resize(len + 4, 0);
+    self
+      .inner
+      .read_exact(&mut self.block)
+      .map_err(|_| "Unexpected end of file.")?;
+    let checksum = u32::from_le_bytes(self.block[len..].try_into().unwrap());
+    self.block.truncate(len);
+    if crc32(&self.block) != checksum {
+      return Err("Block checksum does not match.");
+    }
+    self.position = 0;
+    self.remaining = count;
+    Ok(true)
+  }
+  fn next_record(&mut self) -> Result<Option<T>, &'static str> {
+    if self.remaining == 0 {
+      if self.position != self.block.len() {
+        return Err("Block has bytes left over.");
+      }
+      if !self.next_block()? {
+        return Ok(None);
+      }
+    }
+    let mut input = &self.block[self.position..];
+    let record = T::decode(&mut input)?;
+    self.position = self.block.len() - input.len();
+    self.remaining -= 1;
+    Ok(Some(record))
+  }
+}
+
+impl<R: Read, T: Decode> Iterator for Reader<R, T> {
+  type Item = Result<T, &'static str>;
+
+  // stops after the first error
+  fn next(&mut self) -> Option<Self::Item> {
+    if self.failed {
+      return None;
+    }
+    let result = self.next_record().transpose();
+    self.failed = matches!(result, Some(Err(_)));
+    result
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::Rng;
+
+  fn random_deal(rng: &mut Rng) -> Deal {
+    let mut deck = Card::all();
+    let dealt = 3 + rng.below(3);
+    rng.partial_shuffle(&mut deck, 2 + dealt);
+    Deal::partial([deck[0], deck[1]], &deck[2..2 + dealt]).unwrap()
+  }
+
+  fn random_action(rng: &mut Rng) -> Action {
+    // amounts of every size, up to the full 64 bits
+    let amount = rng.next_u64() >> rng.below(64);
+    match rng.below(5) {
+      0 => Action::Fold,
+      1 => Action::Check,
+      2 => Action::Call(amount),
+      3 => Action::Bet(amount),
+      _ => Action::Raise(amount),
+    }
+  }
+
+  #[test]
+  fn test_cards() {
+    for card in Card::all() {
+      let byte = card_byte(card);
+      assert!(byte < 52);
+      assert_eq!(card_from_byte(byte), Ok(card));
+      assert_eq!(from_bytes::<Card>(&to_bytes(&card)), Ok(card));
+    }
+    assert_eq!(card_byte("AC".parse().unwrap()), 0);
+    assert_eq!(card_byte("2C".parse().unwrap()), 1);
+    assert_eq!(card_byte("KS".parse().unwrap()), 51);
+    assert!(card_from_byte(52).is_err());
+  }
+  #[test]
+  fn test_varints() {
+    let mut rng = Rng::new(48);
+    for value in [0, 1, 127, 128, 300, u64::MAX] {
+      assert_eq!(from_bytes::<u64>(&to_bytes(&value)), Ok(value));
+    }
+    assert_eq!(to_bytes(&300u64), vec![0xac, 0x02]);
+    assert_eq!(to_bytes(&-1i64), vec![1]);
+    for _ in 0..1000 {
+      let value = rng.next_u64() >> rng.below(64);
+      assert_eq!(from_bytes::<u64>(&to_bytes(&value)), Ok(value));
+      let signed = value as i64;
+      assert_eq!(from_bytes::<i64>(&to_bytes(&signed)), Ok(signed));
+    }
+    assert!(from_bytes::<u64>(&[0xff; 11]).is_err());
+    assert!(from_bytes::<u64>(&[0x80]).is_err());
+  }
+  #[test]
+  fn test_deals_and_actions() {
+    let mut rng = Rng::new(7);
+    for _ in 0..1000 {
+      let deal = random_deal(&mut rng);
+      let bytes = to_bytes(&deal);
+      assert_eq!(
+        bytes.len(),
+        1 + (6 * (2 + deal.community().len())).div_ceil(8)
+      );
+      assert_eq!(from_bytes::<Deal>(&bytes), Ok(deal));
+      let actions: Vec<Action> = (0..rng.below(20))
+        .map(|_| random_action(&mut rng))
+        .collect();
+      assert_eq!(from_bytes::<Vec<Action>>(&to_bytes(&actions)), Ok(actions));
+    }
+    assert!(from_bytes::<Deal>(&[6, 0, 0, 0, 0, 0, 0]).is_err());
+    assert!(from_bytes::<Action>(&[9]).is_err());
+  }
+  #[test]
+  fn test_garbage_never_panics() {
+    let mut rng = Rng::new(99);
+    for _ in 0..5000 {
+      let bytes: Vec<u8> = (0..rng.below(24)).map(|_| rng.next_u64() as u8).collect();
+      let _ = from_bytes::<(Deal, Vec<Action>)>(&bytes);
+      let _ = from_bytes::<Vec<Street>>(&bytes);
+    }
+  }
+  #[test]
+  fn test_file() {
+    let mut rng = Rng::new(2021);
+    // enough records to fill several blocks
+    let records: Vec<(Deal, Vec<Action>)> = (0..20_000)
+      .map(|_| {
+        let deal = random_deal(&mut rng);
+        let actions = (0..rng.below(8)).map(|_| random_action(&mut rng)).collect();
+        (deal, actions)
+      })
+      .collect();
+    let mut writer = Writer::new(Vec::new()).unwrap();
+    for record in &records {
+      writer.write(record).unwrap();
+    }
+    let file = writer.finish().unwrap();
+    assert_eq!(&file[..4], b"PKRB");
+    let reader = Reader::<_, (Deal, Vec<Action>)>::new(file.as_slice()).unwrap();
+    let read: Vec<_> = reader.collect::<Result<_, _>>().unwrap();
+    assert_eq!(read, records);
+
+    // an empty file is just the header
+    let empty = Writer::<_, Deal>::new(Vec::new())
+      .unwrap()
+      .finish()
+      .unwrap();
+    assert_eq!(empty.len(), 6);
+    assert_eq!(Reader::<_, Deal>::new(empty.as_slice()).unwrap().count(), 0);
+  }
+  #[test]
+  fn test_corrupt_file() {
+    let mut writer = Writer::new(Vec::new()).unwrap();
+    let mut rng = Rng::new(3);
+    for _ in 0..100 {
+      writer.write(&random_deal(&mut rng)).unwrap();
+    }
+    let file = writer.finish().unwrap();
+    let read = |bytes: &[u8]| -> Result<Vec<Deal>, &'static str> { Reader::new(bytes)?.collect() };
+    assert!(read(&file).is_ok());
+    let mut flipped = file.clone();
+    flipped[40] ^= 1;
+    assert_eq!(read(&flipped), Err("Block checksum does not match."));
+    assert!(read(&file[..file.len() - 1]).is_err());
+    assert_eq!(file[4..6], [2, 0]);
+    let mut version = file.clone();
+    version[4] = 1;
+    assert_eq!(read(&version), Err("Unsupported hand record version."));
+    assert!(read(b"PK").is_err());
+    // random damage anywhere is caught or read cleanly, never a panic
+    for _ in 0..500 {
+      let mut damaged = file.clone();
+      let at = rng.below(damaged.len());
+      damaged[at] = rng.next_u64() as u8;
+      let _ = read(&damaged);
+    }
+  }
+}
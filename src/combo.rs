@@ -0,0 +1,107 @@
+use crate::Card;
+use std::fmt;
+use std::str::FromStr;
+
+// Two hole cards, the higher card first.
+#[derive(Clone, Debug, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Combo {
+  cards: [Card; 2],
+}
+
+impl Combo {
+  // for cards that are different by construction; try_new checks
+  pub(crate) fn new(a: Card, b: Card) -> Self {
+    Self::try_new(a, b).expect("A combo needs two different cards")
+  }
+  pub fn try_new(a: Card, b: Card) -> Result<Self, &'static str> {
+    if a == b {
+      return Err("A combo needs two different cards.");
+    }
+    Ok(Self {
+      cards: if a > b { [a, b] } else { [b, a] },
+    })
+  }
+  pub fn cards(&self) -> [Card; 2] {
+    self.cards
+  }
+  pub fn contains(&self, card: Card) -> bool {
+    self.cards.contains(&card)
+  }
+  pub fn overlaps(&self, other: &Self) -> bool {
+    self.cards.iter().any(|&c| other.contains(c))
+  }
+  pub fn is_blocked(&self, dead: &[Card]) -> bool {
+    self.cards.iter().any(|c| dead.contains(c))
+  }
+  pub fn is_pair(&self) -> bool {
+    self.cards[0].face() == self.cards[1].face()
+  }
+  pub fn is_suited(&self) -> bool {
+    self.cards[0].suit() == self.cards[1].suit()
+  }
+  // all 1326 starting hands
+  pub fn all() -> Vec<Self> {
+    let deck = Card::all();
+    let mut combos = Vec::with_capacity(1326);
+    for (i, &a) in deck.iter().enumerate() {
+      for &b in &deck[i + 1..] {
+        combos.push(Self::new(a, b));
+      }
+    }
+    combos.sort();
+    combos
+  }
+}
+
+impl fmt::Display for Combo {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}{}", self.cards[0], self.cards[1])
+  }
+}
+
+impl FromStr for Combo {
+  type Err = &'static str;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    if s.len() != 4 || !s.is_char_boundary(2) {
+      return Err("Invalid combo.");
+    }
+    let a: Card = s[0..2].parse()?;
+    let b: Card = s[2..4].parse()?;
+    Self::try_new(a, b)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_from_str() {
+    let combo: Combo = "KhAh".parse().unwrap();
+    assert_eq!(combo.to_string(), "AHKH");
+    assert!(combo.is_suited());
+    assert!(!combo.is_pair());
+    assert!("AhAh".parse::<Combo>().is_err());
+    let ace = "AH".parse().unwrap();
+    assert_eq!(
+      Combo::try_new(ace, ace),
+      Err("A combo needs two different cards.")
+    );
+    assert!("AhK".parse::<Combo>().is_err());
+  }
+  #[test]
+  fn test_all() {
+    let combos = Combo::all();
+    assert_eq!(combos.len(), 1326);
+    assert_eq!(combos.iter().filter(|c| c.is_pair()).count(), 78);
+    assert_eq!(combos.iter().filter(|c| c.is_suited()).count(), 312);
+  }
+  #[test]
+  fn test_blocked() {
+    let combo: Combo = "AhKh".parse().unwrap();
+    assert!(combo.is_blocked(&["2C".parse().unwrap(), "KH".parse().unwrap()]));
+    assert!(!combo.is_blocked(&["AS".parse().unwrap()]));
+    assert!(combo.overlaps(&"AhQd".parse().unwrap()));
+  }
+}
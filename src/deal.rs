@@ -1,5 +1,8 @@
+use crate::eval::evaluate;
 use crate::Card;
 use crate::Hand;
+use crate::Rank;
+use crate::Street;
 use std::fmt;
 
 fn combinations(arr: &[Card], size: usize) -> Vec<Vec<Card>> {
@@ -25,26 +28,82 @@ fn combinations(arr: &[Card], size: usize) -> Vec<Vec<Card>> {
   result
 }
 
+// Hole cards and the community cards dealt so far: a flop, turn or river.
 #[derive(Clone, Debug, Copy, Eq, PartialEq)]
 pub struct Deal {
   hole: [Card; 2],
   community: [Card; 5],
+  dealt: usize,
 }
 
 impl Deal {
   pub fn new(hole: [Card; 2], community: [Card; 5]) -> Self {
-    Self { hole, community }
-  }
-  pub fn combine(&self) -> [Card; 7] {
-    [
-      self.hole[0],
-      self.hole[1],
-      self.community[0],
-      self.community[1],
-      self.community[2],
-      self.community[3],
-      self.community[4],
-    ]
+    Self {
+      hole,
+      community,
+      dealt: 5,
+    }
+  }
+  // a deal with three, four or five community cards
+  pub fn partial(hole: [Card; 2], community: &[Card]) -> Result<Self, &'static str> {
+    if !(3..=5).contains(&community.len()) {
+      return Err("A deal needs three to five community cards.");
+    }
+    let mut cards = hole.to_vec();
+    cards.extend_from_slice(community);
+    cards.sort();
+    cards.dedup();
+    if cards.len() != community.len() + 2 {
+      return Err("Deal has a repeated card.");
+    }
+    let mut full = [community[0]; 5];
+    full[..community.len()].copy_from_slice(community);
+    Ok(Self {
+      hole,
+      community: full,
+      dealt: community.len(),
+    })
+  }
+  pub fn hole(&self) -> [Card; 2] {
+    self.hole
+  }
+  pub fn community(&self) -> &[Card] {
+    &self.community[..self.dealt]
+  }
+  pub fn street(&self) -> Street {
+    match self.dealt {
+      3 => Street::Flop,
+      4 => Street::Turn,
+      _ => Street::River,
+    }
+  }
+  pub fn contains(&self, card: Card) -> bool {
+    self.hole.contains(&card) || self.community().contains(&card)
+  }
+  // the same deal with the next community card
+  pub fn with(&self, card: Card) -> Result<Self, &'static str> {
+    if self.dealt == 5 {
+      return Err("The board is complete.");
+    }
+    if self.contains(card) {
+      return Err("Card is already dealt.");
+    }
+    let mut next = *self;
+    next.community[self.dealt] = card;
+    next.dealt += 1;
+    Ok(next)
+  }
+  // cards not in the deal
+  pub fn unseen(&self) -> Vec<Card> {
+    Card::all()
+      .into_iter()
+      .filter(|c| !self.contains(*c))
+      .collect()
+  }
+  pub fn combine(&self) -> Vec<Card> {
+    let mut cards = self.hole.to_vec();
+    cards.extend_from_slice(self.community());
+    cards
   }
   pub fn combinations(&self) -> Vec<Hand> {
     combinations(&self.combine(), 5)
@@ -57,21 +116,19 @@ impl Deal {
     hands.sort();
     hands.pop().unwrap()
   }
+  // the rank of the best hand, without building every Hand
+  pub fn rank(&self) -> Rank {
+    evaluate(&self.combine())
+  }
 }
 
 impl fmt::Display for Deal {
   fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-    write!(
-      f,
-      "{} {} + {} {} {} {} {}",
-      self.hole[0],
-      self.hole[1],
-      self.community[0],
-      self.community[1],
-      self.community[2],
-      self.community[3],
-      self.community[4],
-    )
+    write!(f, "{} {} +", self.hole[0], self.hole[1])?;
+    for card in self.community() {
+      write!(f, " {}", card)?;
+    }
+    Ok(())
   }
 }
 
@@ -123,4 +180,27 @@ mod tests {
       ]
     );
   }
+  #[test]
+  fn test_partial() {
+    let hole = ["AS", "3S"].map(|c| c.parse().unwrap());
+    let flop: Vec<Card> = ["9S", "TS", "2D"]
+      .iter()
+      .map(|c| c.parse().unwrap())
+      .collect();
+    let deal = Deal::partial(hole, &flop).unwrap();
+    assert_eq!(deal.street(), Street::Flop);
+    assert_eq!(deal.to_string(), "AS 3S + 9S TS 2D");
+    assert_eq!(deal.combinations().len(), 1);
+    assert_eq!(deal.unseen().len(), 47);
+    let turn = deal.with("KS".parse().unwrap()).unwrap();
+    assert_eq!(turn.community().len(), 4);
+    assert_eq!(turn.combinations().len(), 6);
+    assert_eq!(turn.best_hand().to_string(), "AS KS TS 9S 3S");
+    assert!(turn.with("KS".parse().unwrap()).is_err());
+    let river = turn.with("2C".parse().unwrap()).unwrap();
+    assert_eq!(river.street(), Street::River);
+    assert!(river.with("2H".parse().unwrap()).is_err());
+    assert!(Deal::partial(hole, &flop[..2]).is_err());
+    assert!(Deal::partial(hole, &["AS".parse().unwrap(), flop[0], flop[1]]).is_err());
+  }
 }
@@ -0,0 +1,342 @@
+// Dealing rules for a variant: the rounds of cards in the order they come
+// off the deck, and whether hole cards go round the table one at a time or
+// a seat's cards together. Dealing starts with the seat left of the button
+// and every card keeps its position in the deck, so a hand can be checked
+// against the shuffle afterwards.
+
+use crate::Card;
+use crate::Deal;
+use crate::Order;
+use crate::Permutation;
+use std::fmt;
+
+#[derive(Clone, Debug, Copy, Eq, PartialEq)]
+pub enum Round {
+  // one card to every seat, face up or down
+  Hole { up: bool },
+  // community cards, after burning a card if burn is set
+  Board { cards: usize, burn: bool },
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Rules {
+  pub rounds: Vec<Round>,
+  // how back to back hole rounds are dealt
+  pub order: Order,
+}
+
+#[derive(Clone, Debug, Copy, Eq, PartialEq)]
+pub enum Destination {
+  Seat(usize),
+  Board,
+  Burn,
+}
+
+#[derive(Clone, Debug, Copy, Eq, PartialEq)]
+pub struct Dealt {
+  pub card: Card,
+  // from 0, the top of the deck
+  pub position: usize,
+  pub to: Destination,
+  pub up: bool,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Dealing {
+  pub seats: usize,
+  pub button: usize,
+  pub cards: Vec<Dealt>,
+}
+
+fn down(n: usize) -> Vec<Round> {
+  vec![Round::Hole { up: false }; n]
+}
+
+fn streets() -> [Round; 3] {
+  [
+    Round::Board {
+      cards: 3,
+      burn: true,
+    },
+    Round::Board {
+      cards: 1,
+      burn: true,
+    },
+    Round::Board {
+      cards: 1,
+      burn: true,
+    },
+  ]
+}
+
+impl Rules {
+  pub fn holdem() -> Self {
+    let mut rounds = down(2);
+    rounds.extend(streets());
+    Self {
+      rounds,
+      order: Order::RoundRobin,
+    }
+  }
+  pub fn omaha() -> Self {
+    let mut rounds = down(4);
+    rounds.extend(streets());
+    Self {
+      rounds,
+      order: Order::RoundRobin,
+    }
+  }
+  // two down and one up on third street, up cards to sixth, one down on
+  // seventh; no burns
+  pub fn seven_card_stud() -> Self {
+    let mut rounds = down(2);
+    rounds.extend([Round::Hole { up: true }; 4]);
+    rounds.push(Round::Hole { up: false });
+    Self {
+      rounds,
+      order: Order::RoundRobin,
+    }
+  }
+  // How Deck deals its nine cards: two rounds of hole cards and five
+  // community cards, nothing burned.
+  pub fn unburned_holdem() -> Self {
+    Self {
+      rounds: vec![
+        Round::Hole { up: false },
+        Round::Hole { up: false },
+        Round::Board {
+          cards: 5,
+          burn: false,
+        },
+      ],
+      order: Order::RoundRobin,
+    }
+  }
+  pub fn cards_needed(&self, seats: usize) -> usize {
+    self
+      .rounds
+      .iter()
+      .map(|round| match *round {
+        Round::Hole { .. } => seats,
+        Round::Board { cards, burn } => cards + burn as usize,
+      })
+      .sum()
+  }
+
+  pub fn deal(
+    &self,
+    perm: &Permutation,
+    seats: usize,
+    button: usize,
+  ) -> Result<Dealing, &'static str> {
+    if seats < 2 {
+      return Err("A deal needs at least two seats.");
+    }
+    if button >= seats {
+      return Err("The button must be one of the seats.");
+    }
+    if self.cards_needed(seats) > 52 {
+      return Err("Not enough cards for every seat.");
+    }
+    // seats in the order they are dealt to, starting left of the button
+    let order: Vec<usize> = (1..=seats).map(|i| (button + i) % seats).collect();
+    let mut cards = Vec::with_capacity(self.cards_needed(seats));
+    let mut deal = |to: Destination, up: bool| {
+      let position = cards.len();
+      cards.push(Dealt {
+        card: perm.cards()[position],
+        position,
+        to,
+        up,
+      });
+    };
+    let mut i = 0;
+    while i < self.rounds.len() {
+      match self.rounds[i] {
+        Round::Board { cards, burn } => {
+          if burn {
+            deal(Destination::Burn, false);
+          }
+          for _ in 0..cards {
+            deal(Destination::Board, true);
+          }
+          i += 1;
+        }
+        Round::Hole { .. } => {
+          let run = self.rounds[i..]
+            .iter()
+            .take_while(|r| matches!(r, Round::Hole { .. }))
+            .count();
+          let up = |r: usize| matches!(self.rounds[i + r], Round::Hole { up: true });
+          match self.order {
+            Order::RoundRobin => {
+              for r in 0..run {
+                for &seat in &order {
+                  deal(Destination::Seat(seat), up(r));
+                }
+              }
+            }
+            Order::Block => {
+              for &seat in &order {
+                for r in 0..run {
+                  deal(Destination::Seat(seat), up(r));
+                }
+              }
+            }
+          }
+          i += run;
+        }
+      }
+    }
+    Ok(Dealing {
+      seats,
+      button,
+      cards,
+    })
+  }
+}
+
+impl Dealing {
+  fn to(&self, to: Destination) -> impl Iterator<Item = &Dealt> {
+    self.cards.iter().filter(move |d| d.to == to)
+  }
+  pub fn hole(&self, seat: usize) -> Vec<Card> {
+    self.to(Destination::Seat(seat)).map(|d| d.card).collect()
+  }
+  // the cards of a seat the other players can see
+  pub fn up(&self, seat: usize) -> Vec<Card> {
+    self
+      .to(Destination::Seat(seat))
+      .filter(|d| d.up)
+      .map(|d| d.card)
+      .collect()
+  }
+  pub fn board(&self) -> Vec<Card> {
+    self.to(Destination::Board).map(|d| d.card).collect()
+  }
+  pub fn burned(&self) -> Vec<Card> {
+    self.to(Destination::Burn).map(|d| d.card).collect()
+  }
+  pub fn position(&self, card: Card) -> Option<usize> {
+    self
+      .cards
+      .iter()
+      .find(|d| d.card == card)
+      .map(|d| d.position)
+  }
+  // A hold'em deal for each seat, for dealings with two hole cards and five
+  // on the board.
+  pub fn deals(&self) -> Result<Vec<Deal>, &'static str> {
+    let community: [Card; 5] = self
+      .board()
+      .try_into()
+      .map_err(|_| "A hold'em deal has five community cards.")?;
+    (0..self.seats)
+      .map(|seat| {
+        let hole: [Card; 2] = self
+          .hole(seat)
+          .try_into()
+          .map_err(|_| "A hold'em deal has two hole cards.")?;
+        Ok(Deal::new(hole, community))
+      })
+      .collect()
+  }
+}
+
+// One line per card, for audits:
+//   0 KS seat 1 down
+impl fmt::Display for Dealing {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for (i, dealt) in self.cards.iter().enumerate() {
+      if i > 0 {
+        writeln!(f)?;
+      }
+      write!(f, "{} {} ", dealt.position, dealt.card)?;
+      match dealt.to {
+        Destination::Seat(seat) => {
+          let face = if dealt.up { "up" } else { "down" };
+          write!(f, "seat {} {}", seat, face)?
+        }
+        Destination::Board => write!(f, "board")?,
+        Destination::Burn => write!(f, "burn")?,
+      }
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::Deck;
+
+  fn perm(first: &[u32]) -> Permutation {
+    let mut perm = first.to_vec();
+    let rest: Vec<u32> = (1..=52).filter(|i| !perm.contains(i)).collect();
+    perm.extend(rest);
+    Permutation::new(&perm).unwrap()
+  }
+
+  #[test]
+  fn test_matches_deck() {
+    let first = [40, 41, 42, 43, 48, 49, 50, 51, 52];
+    // seat 0 is left of the button so gets the first card
+    let dealing = Rules::unburned_holdem().deal(&perm(&first), 2, 1).unwrap();
+    assert_eq!(dealing.deals().unwrap(), Deck::new(first).deal());
+    assert_eq!(
+      dealing.deals().unwrap(),
+      perm(&first).deal(2, Order::RoundRobin).unwrap()
+    );
+  }
+  #[test]
+  fn test_holdem() {
+    let shuffle = perm(&[]);
+    let dealing = Rules::holdem().deal(&shuffle, 3, 0).unwrap();
+    assert_eq!(dealing.cards.len(), 6 + 8);
+    // seat 1 first, then 2, then the button
+    assert_eq!(dealing.hole(1), vec![Card::from(1), Card::from(4)]);
+    assert_eq!(dealing.hole(0), vec![Card::from(3), Card::from(6)]);
+    assert_eq!(
+      dealing.burned(),
+      vec![Card::from(7), Card::from(11), Card::from(13)]
+    );
+    assert_eq!(dealing.board(), [8, 9, 10, 12, 14].map(Card::from).to_vec());
+    assert_eq!(dealing.position(Card::from(14)), Some(13));
+    assert_eq!(dealing.position(Card::from(52)), None);
+    assert_eq!(dealing.deals().unwrap().len(), 3);
+    assert!(dealing
+      .to_string()
+      .starts_with("0 AC seat 1 down\n1 2C seat 2 down"));
+
+    let block = Rules {
+      order: Order::Block,
+      ..Rules::holdem()
+    };
+    let dealing = block.deal(&shuffle, 3, 0).unwrap();
+    assert_eq!(dealing.hole(1), vec![Card::from(1), Card::from(2)]);
+    assert_eq!(dealing.hole(0), vec![Card::from(5), Card::from(6)]);
+    assert_eq!(
+      Rules::holdem().deal(&shuffle, 22, 0).unwrap().cards.len(),
+      52
+    );
+    assert!(Rules::holdem().deal(&shuffle, 23, 0).is_err());
+    assert!(Rules::holdem().deal(&shuffle, 3, 3).is_err());
+  }
+  #[test]
+  fn test_stud() {
+    let dealing = Rules::seven_card_stud().deal(&perm(&[]), 7, 6).unwrap();
+    assert_eq!(dealing.cards.len(), 49);
+    assert!(dealing.board().is_empty());
+    for seat in 0..7 {
+      let hole = dealing.hole(seat);
+      assert_eq!(hole.len(), 7);
+      assert_eq!(dealing.up(seat), hole[2..6].to_vec());
+    }
+    assert!(Rules::seven_card_stud().deal(&perm(&[]), 8, 0).is_err());
+    assert!(dealing.deals().is_err());
+    assert_eq!(
+      Rules::omaha().deal(&perm(&[]), 2, 0).unwrap().hole(1).len(),
+      4
+    );
+  }
+}
@@ -11,6 +11,16 @@ impl Deck {
       cards: cards.map(|c| c.into()),
     }
   }
+  pub fn from_cards(cards: [Card; 9]) -> Result<Self, &'static str> {
+    if (1..9).any(|i| cards[..i].contains(&cards[i])) {
+      return Err("A card is repeated.");
+    }
+    Ok(Self { cards })
+  }
+  pub fn cards(&self) -> [Card; 9] {
+    self.cards
+  }
+  // dealing::Rules::unburned_holdem with two seats, for other layouts
   pub fn deal(&self) -> [Deal; 2] {
     let community = self.cards[4..9].try_into().unwrap(); // try_into converts arr slice into an arr
 
@@ -21,6 +31,63 @@ impl Deck {
   }
 }
 
+// How hole cards come off a permutation: one card to each player in turn
+// and then round again, or each player's cards together.
+#[derive(Clone, Debug, Copy, Eq, PartialEq)]
+pub enum Order {
+  RoundRobin,
+  Block,
+}
+
+// A full deck in dealing order, from the 1 to 52 card numbers of
+// Card::from_index.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Permutation {
+  cards: [Card; 52],
+}
+
+impl Permutation {
+  // the most players with two hole cards each and a board, 2 * 23 + 5 <= 52
+  pub const MAX_PLAYERS: usize = 23;
+
+  pub fn new(perm: &[u32]) -> Result<Self, &'static str> {
+    if perm.len() != 52 {
+      return Err("A permutation has 52 cards.");
+    }
+    let mut seen = [false; 52];
+    let mut cards = Card::all();
+    for (i, &idx) in perm.iter().enumerate() {
+      cards[i] = Card::from_index(idx)?;
+      if std::mem::replace(&mut seen[idx as usize - 1], true) {
+        return Err("A card is repeated.");
+      }
+    }
+    Ok(Self { cards })
+  }
+  pub fn cards(&self) -> &[Card; 52] {
+    &self.cards
+  }
+  // Two hole cards for each player, then five community cards.
+  pub fn deal(&self, players: usize, order: Order) -> Result<Vec<Deal>, &'static str> {
+    if !(2..=Self::MAX_PLAYERS).contains(&players) {
+      return Err("A deal needs 2 to 23 players.");
+    }
+    let position = |player: usize, round: usize| match order {
+      Order::RoundRobin => round * players + player,
+      Order::Block => 2 * player + round,
+    };
+    let community: [Card; 5] = self.cards[2 * players..2 * players + 5].try_into().unwrap();
+    Ok(
+      (0..players)
+        .map(|p| {
+          let hole = [self.cards[position(p, 0)], self.cards[position(p, 1)]];
+          Deal::new(hole, community)
+        })
+        .collect(),
+    )
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -32,4 +99,29 @@ mod tests {
     assert_eq!(deal[0].to_string(), "AS 3S + 9S TS JS QS KS");
     assert_eq!(deal[1].to_string(), "2S 4S + 9S TS JS QS KS");
   }
+  #[test]
+  fn test_permutation() {
+    let mut perm: Vec<u32> = vec![40, 41, 42, 43, 48, 49, 50, 51, 52];
+    let rest: Vec<u32> = (1..=52).filter(|i| !perm.contains(i)).collect();
+    perm.extend(rest);
+    let shuffled = Permutation::new(&perm).unwrap();
+    // round robin matches the nine card deck
+    let deals = shuffled.deal(2, Order::RoundRobin).unwrap();
+    assert_eq!(deals, Deck::new(perm[..9].try_into().unwrap()).deal());
+    let deals = shuffled.deal(2, Order::Block).unwrap();
+    assert_eq!(deals[0].to_string(), "AS 2S + 9S TS JS QS KS");
+    assert_eq!(deals[1].to_string(), "3S 4S + 9S TS JS QS KS");
+
+    let deals = shuffled.deal(3, Order::RoundRobin).unwrap();
+    assert_eq!(deals[2].to_string(), "3S TS + JS QS KS AC 2C");
+    assert_eq!(shuffled.deal(23, Order::Block).unwrap().len(), 23);
+    assert!(shuffled.deal(24, Order::Block).is_err());
+    assert!(shuffled.deal(1, Order::Block).is_err());
+
+    assert!(Permutation::new(&perm[..51]).is_err());
+    perm[51] = 0;
+    assert!(Permutation::new(&perm).is_err());
+    perm[51] = perm[0];
+    assert_eq!(Permutation::new(&perm), Err("A card is repeated."));
+  }
 }
@@ -0,0 +1,339 @@
+// All-in equity of a hand or range against a range, heads up.
+//
+// Combos that share a card with the board or with each other are never paired
+// up, so card removal is exact for enumeration and unbiased for sampling.
+
+use crate::Card;
+use crate::Combo;
+use crate::Deal;
+use crate::Range;
+use crate::Rank;
+use crate::Rng;
+use std::cmp::Ordering;
+
+#[derive(Clone, Debug, Copy, Eq, PartialEq)]
+pub enum Method {
+  // every runout of the board for every pair of combos
+  Exhaustive,
+  // random pairs of combos, drawn by weight, with random runouts
+  MonteCarlo { trials: usize, seed: u64 },
+}
+
+// Weighted wins, ties and losses for the hero.
+#[derive(Clone, Debug, Default, Copy, PartialEq)]
+pub struct Equity {
+  pub wins: f64,
+  pub ties: f64,
+  pub losses: f64,
+}
+
+impl Equity {
+  pub fn total(&self) -> f64 {
+    self.wins + self.ties + self.losses
+  }
+  // share of the pot, counting a tie as half
+  pub fn equity(&self) -> f64 {
+    (self.wins + self.ties / 2.0) / self.total()
+  }
+  pub fn win_rate(&self) -> f64 {
+    self.wins / self.total()
+  }
+  pub fn tie_rate(&self) -> f64 {
+    self.ties / self.total()
+  }
+  fn add(&mut self, outcome: Ordering, weight: f64) {
+    match outcome {
+      Ordering::Greater => self.wins += weight,
+      Ordering::Equal => self.ties += weight,
+      Ordering::Less => self.losses += weight,
+    }
+  }
+  fn merge(&mut self, other: &Self, weight: f64) {
+    let total = other.total();
+    self.wins += weight * other.wins / total;
+    self.ties += weight * other.ties / total;
+    self.losses += weight * other.losses / total;
+  }
+}
+
+// The hero's equity against one opponent combo.
+#[derive(Clone, Debug, Copy, PartialEq)]
+pub struct Matchup {
+  pub combo: Combo,
+  pub weight: f64,
+  pub equity: Equity,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RangeEquity {
+  pub equity: Equity,
+  pub matchups: Vec<Matchup>,
+}
+
+pub(crate) fn rank(hole: [Card; 2], board: &[Card; 5]) -> Rank {
+  Deal::new(hole, *board).rank()
+}
+
+fn showdown(hero: Combo, villain: Combo, board: &[Card; 5]) -> Ordering {
+  rank(hero.cards(), board).cmp(&rank(villain.cards(), board))
+}
+
+// cards not on the board or in either hand
+fn stub(board: &[Card], hero: Combo, villain: Combo) -> Vec<Card> {
+  Card::all()
+    .into_iter()
+    .filter(|c| !board.contains(c) && !hero.contains(*c) && !villain.contains(*c))
+    .collect()
+}
+
+fn complete(board: &[Card], extra: &[Card]) -> [Card; 5] {
+  let mut full = board.to_vec();
+  full.extend_from_slice(&extra[..5 - board.len()]);
+  full.try_into().unwrap()
+}
+
+// Calls f with every way of choosing k cards from the deck.
+pub(crate) fn for_each_subset(deck: &[Card], k: usize, f: &mut impl FnMut(&[Card])) {
+  let mut picked = Vec::with_capacity(k);
+  fn recurse(deck: &[Card], k: usize, picked: &mut Vec<Card>, f: &mut impl FnMut(&[Card])) {
+    if picked.len() == k {
+      return f(picked);
+    }
+    let needed = k - picked.len();
+    for i in 0..=deck.len() - needed {
+      picked.push(deck[i]);
+      recurse(&deck[i + 1..], k, picked, f);
+      picked.pop();
+    }
+  }
+  if k <= deck.len() {
+    recurse(deck, k, &mut picked, f);
+  }
+}
+
+fn enumerate(hero: Combo, villain: Combo, board: &[Card]) -> Equity {
+  let mut equity = Equity::default();
+  let deck = stub(board, hero, villain);
+  for_each_subset(&deck, 5 - board.len(), &mut |extra| {
+    equity.add(showdown(hero, villain, &complete(board, extra)), 1.0);
+  });
+  equity
+}
+
+fn validate(board: &[Card]) -> Result<(), &'static str> {
+  if board.len() > 5 {
+    return Err("A board has at most five cards.");
+  }
+  let mut sorted = board.to_vec();
+  sorted.sort();
+  sorted.dedup();
+  if sorted.len() != board.len() {
+    return Err("Board has a repeated card.");
+  }
+  Ok(())
+}
+
+// Picks combos by weight using a running total of the weights.
+struct Sampler {
+  combos: Vec<(Combo, f64)>,
+  cumulative: Vec<f64>,
+}
+
+impl Sampler {
+  fn new(combos: Vec<(Combo, f64)>) -> Self {
+    let cumulative = combos
+      .iter()
+      .scan(0.0, |total, (_, w)| {
+        *total += w;
+        Some(*total)
+      })
+      .collect();
+    Self { combos, cumulative }
+  }
+  fn sample(&self, rng: &mut Rng) -> usize {
+    let target = rng.next_f64() * self.cumulative[self.cumulative.len() - 1];
+    self
+      .cumulative
+      .partition_point(|&c| c <= target)
+      .min(self.combos.len() - 1)
+  }
+}
+
+pub fn range_vs_range(
+  hero: &Range,
+  villain: &Range,
+  board: &[Card],
+  method: Method,
+) -> Result<RangeEquity, &'static str> {
+  validate(board)?;
+  if let Method::MonteCarlo { trials: 0, .. } = method {
+    return Err("Monte Carlo needs at least one trial.");
+  }
+  let heroes: Vec<(Combo, f64)> = hero.without(board).combos().collect();
+  let villains: Vec<(Combo, f64)> = villain.without(board).combos().collect();
+  let compatible = heroes
+    .iter()
+    .any(|(h, _)| villains.iter().any(|(v, _)| !h.overlaps(v)));
+  if !compatible {
+    return Err("No combos left to compare.");
+  }
+  let mut matchups: Vec<Matchup> = villains
+    .iter()
+    .map(|&(combo, weight)| Matchup {
+      combo,
+      weight,
+      equity: Equity::default(),
+    })
+    .collect();
+  let mut equity = Equity::default();
+  match method {
+    Method::Exhaustive => {
+      for matchup in matchups.iter_mut() {
+        for &(h, weight) in heroes.iter().filter(|(h, _)| !h.overlaps(&matchup.combo)) {
+          let result = enumerate(h, matchup.combo, board);
+          matchup.equity.merge(&result, weight);
+          equity.merge(&result, weight * matchup.weight);
+        }
+      }
+    }
+    Method::MonteCarlo { trials, seed } => {
+      let mut rng = Rng::new(seed);
+      let hero_sampler = Sampler::new(heroes);
+      let villain_sampler = Sampler::new(villains);
+      let mut trial = 0;
+      while trial < trials {
+        let h = hero_sampler.combos[hero_sampler.sample(&mut rng)].0;
+        let v = villain_sampler.sample(&mut rng);
+        let matchup = &mut matchups[v];
+        if h.overlaps(&matchup.combo) {
+          continue;
+        }
+        let mut deck = stub(board, h, matchup.combo);
+        rng.partial_shuffle(&mut deck, 5 - board.len());
+        let outcome = showdown(h, matchup.combo, &complete(board, &deck));
+        matchup.equity.add(outcome, 1.0);
+        equity.add(outcome, 1.0);
+        trial += 1;
+      }
+    }
+  }
+  matchups.retain(|m| m.equity.total() > 0.0);
+  Ok(RangeEquity { equity, matchups })
+}
+
+pub fn hand_vs_range(
+  hand: Combo,
+  villain: &Range,
+  board: &[Card],
+  method: Method,
+) -> Result<RangeEquity, &'static str> {
+  if hand.is_blocked(board) {
+    return Err("Hand shares a card with the board.");
+  }
+  let mut hero = Range::new();
+  hero.insert(hand, 1.0);
+  range_vs_range(&hero, &villain.without(&hand.cards()), board, method)
+}
+
+pub fn hand_vs_hand(
+  hero: Combo,
+  villain: Combo,
+  board: &[Card],
+  method: Method,
+) -> Result<Equity, &'static str> {
+  let mut range = Range::new();
+  range.insert(villain, 1.0);
+  if hero.overlaps(&villain) {
+    return Err("Hands share a card.");
+  }
+  Ok(hand_vs_range(hero, &range, board, method)?.equity)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn combo(s: &str) -> Combo {
+    s.parse().unwrap()
+  }
+  fn cards(s: &str) -> Vec<Card> {
+    s.split_whitespace().map(|c| c.parse().unwrap()).collect()
+  }
+
+  #[test]
+  fn test_river() {
+    let board = cards("2C 7D 9H JS KD");
+    let equity = hand_vs_hand(combo("AhAs"), combo("KhKs"), &board, Method::Exhaustive).unwrap();
+    assert_eq!(equity.equity(), 0.0);
+    let equity = hand_vs_hand(combo("AhAs"), combo("AdAc"), &board, Method::Exhaustive).unwrap();
+    assert_eq!(equity.tie_rate(), 1.0);
+    assert_eq!(equity.equity(), 0.5);
+  }
+  #[test]
+  fn test_turn() {
+    // nine hearts, two queens for Broadway and three aces, out of 44
+    let board = cards("2H 7H JS KD");
+    let equity = hand_vs_hand(combo("AhTh"), combo("KsQs"), &board, Method::Exhaustive).unwrap();
+    assert!((equity.win_rate() - 14.0 / 44.0).abs() < 1e-12, "{:?}", equity);
+    assert_eq!(equity.ties, 0.0);
+  }
+  #[test]
+  fn test_card_removal() {
+    // the villain can't hold the aces in the hero's hand or on the board
+    let board = cards("AD 7C 2S 9H");
+    let result = hand_vs_range(
+      combo("AhAs"),
+      &"AA, KK".parse().unwrap(),
+      &board,
+      Method::Exhaustive,
+    )
+    .unwrap();
+    let held: Vec<Combo> = result.matchups.iter().map(|m| m.combo).collect();
+    assert_eq!(held.len(), 6);
+    assert!(held.iter().all(|c| c.to_string().starts_with('K')));
+    assert!(result.equity.equity() > 0.9);
+  }
+  #[test]
+  fn test_range_vs_range() {
+    let hero: Range = "AA".parse().unwrap();
+    let villain: Range = "KK".parse().unwrap();
+    let board = cards("2C 7D 9H JS");
+    let exact = range_vs_range(&hero, &villain, &board, Method::Exhaustive).unwrap();
+    let sampled = range_vs_range(
+      &hero,
+      &villain,
+      &board,
+      Method::MonteCarlo {
+        trials: 4000,
+        seed: 1,
+      },
+    )
+    .unwrap();
+    assert_eq!(exact.matchups.len(), 6);
+    assert!((exact.equity.equity() - sampled.equity.equity()).abs() < 0.02);
+    assert!((sampled.equity.total() - 4000.0).abs() < 1e-9);
+  }
+  #[test]
+  fn test_preflop_monte_carlo() {
+    let method = Method::MonteCarlo {
+      trials: 3000,
+      seed: 42,
+    };
+    let equity = hand_vs_hand(combo("AhAs"), combo("KdKc"), &[], method).unwrap();
+    // about 82% for aces against kings
+    assert!((equity.equity() - 0.82).abs() < 0.03, "{:?}", equity);
+  }
+  #[test]
+  fn test_invalid() {
+    let aces: Range = "AA".parse().unwrap();
+    let board = cards("AH AS AD");
+    assert!(hand_vs_range(combo("AhKh"), &aces, &board, Method::Exhaustive).is_err());
+    assert!(hand_vs_range(combo("AcKh"), &aces, &board, Method::Exhaustive).is_err());
+    assert!(range_vs_range(&aces, &aces, &cards("2C 2C"), Method::Exhaustive).is_err());
+    let none = Method::MonteCarlo { trials: 0, seed: 1 };
+    assert_eq!(
+      hand_vs_hand(combo("AhKh"), combo("QsQd"), &[], none),
+      Err("Monte Carlo needs at least one trial.")
+    );
+  }
+}
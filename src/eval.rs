@@ -0,0 +1,138 @@
+// A fast path to the Rank of the best five cards out of five to seven,
+// working on face bitmasks instead of trying every five card Hand. It gives
+// the same answer as Deal::best_hand().rank().
+
+use crate::Card;
+use crate::Face;
+use crate::Rank;
+
+fn face(i: usize) -> Face {
+  Face::all()[i]
+}
+
+// The high card of the best straight in a face mask, Ace playing low too.
+fn straight(mask: u16) -> Option<Face> {
+  let wide = (mask << 1) | (mask >> 12);
+  (4..=13)
+    .rev()
+    .find(|&high| (wide >> (high - 4)) & 0b11111 == 0b11111)
+    .map(|high| face(high - 1))
+}
+
+// the n highest faces in a mask
+fn top<const N: usize>(mask: u16) -> [Face; N] {
+  let mut faces = [Face::Two; N];
+  let mut found = 0;
+  for i in (0..13).rev() {
+    if found < N && mask & (1 << i) != 0 {
+      faces[found] = face(i);
+      found += 1;
+    }
+  }
+  faces
+}
+
+fn highest(mask: u16) -> Face {
+  top::<1>(mask)[0]
+}
+
+pub fn evaluate(cards: &[Card]) -> Rank {
+  assert!(
+    (5..=7).contains(&cards.len()),
+    "Evaluate five to seven cards."
+  );
+  let mut suits = [0u16; 4];
+  let mut counts = [0u8; 13];
+  for card in cards {
+    suits[card.suit() as usize] |= 1 << card.face() as usize;
+    counts[card.face() as usize] += 1;
+  }
+
+  if let Some(&flush) = suits.iter().find(|s| s.count_ones() >= 5) {
+    return match straight(flush) {
+      Some(Face::Ace) => Rank::RoyalFlush,
+      Some(high) => Rank::StraightFlush(high),
+      None => Rank::Flush(top(flush)),
+    };
+  }
+
+  // faces seen at least once, twice, three and four times
+  let mut seen = [0u16; 4];
+  for (i, &n) in counts.iter().enumerate() {
+    for mask in seen.iter_mut().take(n as usize) {
+      *mask |= 1 << i;
+    }
+  }
+  let [any, pairs, trips, quads] = seen;
+
+  if quads != 0 {
+    let quad = highest(quads);
+    return Rank::FourOfAKind(quad, top(any & !(1 << quad as usize)));
+  }
+  if trips != 0 {
+    let trip = highest(trips);
+    let rest = pairs & !(1 << trip as usize);
+    if rest != 0 {
+      return Rank::FullHouse(trip, highest(rest));
+    }
+  }
+  if let Some(high) = straight(any) {
+    return Rank::Straight(high);
+  }
+  if trips != 0 {
+    let trip = highest(trips);
+    return Rank::ThreeOfAKind(trip, top(any & !(1 << trip as usize)));
+  }
+  if pairs.count_ones() >= 2 {
+    let [a, b] = top(pairs);
+    return Rank::TwoPair(a, b, top(any & !(1 << a as usize) & !(1 << b as usize)));
+  }
+  if pairs != 0 {
+    let pair = highest(pairs);
+    return Rank::Pair(pair, top(any & !(1 << pair as usize)));
+  }
+  Rank::HighCard(top(any))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::Deal;
+  use crate::Rng;
+
+  fn cards(s: &str) -> Vec<Card> {
+    s.split_whitespace().map(|c| c.parse().unwrap()).collect()
+  }
+
+  #[test]
+  fn test_categories() {
+    assert_eq!(evaluate(&cards("AS KS QS JS TS 2D 3C")), Rank::RoyalFlush);
+    assert_eq!(
+      evaluate(&cards("AS 2S 3S 4S 5S KD KC")),
+      Rank::StraightFlush(Face::Five)
+    );
+    assert_eq!(
+      evaluate(&cards("AS AD 4C 4H 5S 5D KC")),
+      Rank::TwoPair(Face::Ace, Face::Five, [Face::King])
+    );
+    assert_eq!(
+      evaluate(&cards("7S 7D 7C 4H 4S 4D KC")),
+      Rank::FullHouse(Face::Seven, Face::Four)
+    );
+    assert_eq!(
+      evaluate(&cards("AH 2D 3C 4H 5S")),
+      Rank::Straight(Face::Five)
+    );
+  }
+  #[test]
+  fn test_agrees_with_best_hand() {
+    let mut rng = Rng::new(36);
+    let mut deck = Card::all();
+    for _ in 0..2000 {
+      rng.partial_shuffle(&mut deck, 7);
+      let len = 5 + rng.below(3);
+      let deal = Deal::partial([deck[0], deck[1]], &deck[2..len]).unwrap();
+      assert_eq!(evaluate(&deck[..len]), deal.best_hand().rank(), "{}", deal);
+    }
+  }
+}
@@ -1,7 +1,7 @@
 use std::fmt;
 use std::str::FromStr;
 
-#[derive(Clone, Debug, Copy, Eq, PartialEq, Ord, PartialOrd)]
+#[derive(Clone, Debug, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub enum Face {
   Two,
   Three,
@@ -22,6 +22,17 @@ impl Face {
   pub fn is_ace(&self) -> bool {
     self == &Self::Ace
   }
+  pub fn all() -> [Self; 13] {
+    Self::counters().map(|(_, face)| face)
+  }
+  // The grader numbers faces 1 (Ace) to 13 (King).
+  pub fn number(&self) -> u8 {
+    if self.is_ace() {
+      1
+    } else {
+      *self as u8 + 2
+    }
+  }
   pub fn counters() -> [(u8, Self); 13] {
     [
       (0, Self::Two),
@@ -47,7 +58,7 @@ impl fmt::Display for Face {
       f,
       "{}",
       match self {
-        Self::Ace => "1",
+        Self::Ace => "A",
         Self::Two => "2",
         Self::Three => "3",
         Self::Four => "4",
@@ -56,10 +67,10 @@ impl fmt::Display for Face {
         Self::Seven => "7",
         Self::Eight => "8",
         Self::Nine => "9",
-        Self::Ten => "10",
-        Self::Jack => "11",
-        Self::Queen => "12",
-        Self::King => "13",
+        Self::Ten => "T",
+        Self::Jack => "J",
+        Self::Queen => "Q",
+        Self::King => "K",
       }
     )
   }
@@ -118,4 +129,10 @@ mod tests {
     assert!(Face::Ten > Face::Five);
     assert!(Face::Ace > Face::King);
   }
+  #[test]
+  fn test_number() {
+    assert_eq!(Face::Ace.number(), 1);
+    assert_eq!(Face::Ten.number(), 10);
+    assert_eq!(Face::King.number(), 13);
+  }
 }
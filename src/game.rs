@@ -0,0 +1,330 @@
+// A game of no limit hold'em in the terminal, one person against bots.
+//
+// Stacks carry over from hand to hand and the button moves left. Players
+// who go broke sit out, and the game is over when the person at the
+// keyboard busts, is the last one with chips, or types "quit".
+
+use crate::agent::Agent;
+use crate::agent::View;
+use crate::Action;
+use crate::Card;
+use crate::Deal;
+use crate::Legal;
+use crate::Permutation;
+use crate::Rng;
+use crate::Street;
+use crate::Table;
+use std::io;
+use std::io::BufRead;
+use std::io::Write;
+
+pub enum Seat {
+  Human,
+  Bot(Box<dyn Agent>),
+}
+
+struct Player {
+  name: String,
+  seat: Seat,
+  stack: u64,
+}
+
+pub struct Game<R, W> {
+  players: Vec<Player>,
+  button: usize,
+  small_blind: u64,
+  big_blind: u64,
+  rng: Rng,
+  input: R,
+  output: W,
+}
+
+// What a typed action means, given what is legal. Amounts are raise totals
+// for the street, as elsewhere:
+//   f, fold         c, call, k, check
+//   b 60, bet 60    r 60, raise 60
+//   a, all, allin
+pub fn parse_action(input: &str, legal: &Legal) -> Result<Action, &'static str> {
+  let words: Vec<String> = input.split_whitespace().map(|w| w.to_lowercase()).collect();
+  let words: Vec<&str> = words.iter().map(|w| w.as_str()).collect();
+  match words[..] {
+    ["f" | "fold"] if legal.can_check() => Err("There's no bet to fold to, check instead."),
+    ["f" | "fold"] => Ok(Action::Fold),
+    ["c" | "call" | "k" | "check"] => Ok(legal.call()),
+    ["a" | "all" | "allin"] => legal
+      .max_raise
+      .and_then(|max| legal.raise(max))
+      .ok_or("Raising is not allowed."),
+    ["b" | "bet" | "r" | "raise", amount] => {
+      let to: u64 = amount.parse().map_err(|_| "The amount must be a number.")?;
+      match (legal.min_raise, legal.max_raise) {
+        (Some(min), Some(max)) if to >= min && to <= max => Ok(legal.raise(to).unwrap()),
+        (Some(_), Some(_)) => Err("That size isn't allowed."),
+        _ => Err("Raising is not allowed."),
+      }
+    }
+    _ => Err("Type fold, call, check, bet or raise with an amount, or all."),
+  }
+}
+
+fn cards(cards: &[Card]) -> String {
+  cards
+    .iter()
+    .map(|c| c.to_string())
+    .collect::<Vec<_>>()
+    .join(" ")
+}
+
+// The options as a prompt, e.g. "fold, call 20, raise 40-1000".
+fn options(legal: &Legal) -> String {
+  let mut options = Vec::new();
+  if !legal.can_check() {
+    options.push("fold".to_string());
+  }
+  options.push(match legal.call() {
+    Action::Call(amount) => format!("call {}", amount),
+    _ => "check".to_string(),
+  });
+  if let (Some(min), Some(max)) = (legal.min_raise, legal.max_raise) {
+    let verb = if legal.current_bet == 0 {
+      "bet"
+    } else {
+      "raise"
+    };
+    if min == max {
+      options.push(format!("{} {}", verb, min));
+    } else {
+      options.push(format!("{} {}-{}", verb, min, max));
+    }
+  }
+  options.join(", ")
+}
+
+impl<R: BufRead, W: Write> Game<R, W> {
+  pub fn new(small_blind: u64, big_blind: u64, seed: u64, input: R, output: W) -> Self {
+    Self {
+      players: Vec::new(),
+      button: 0,
+      small_blind,
+      big_blind,
+      rng: Rng::new(seed),
+      input,
+      output,
+    }
+  }
+  pub fn sit(&mut self, name: &str, seat: Seat, stack: u64) {
+    self.players.push(Player {
+      name: name.to_string(),
+      seat,
+      stack,
+    });
+  }
+  pub fn stacks(&self) -> Vec<u64> {
+    self.players.iter().map(|p| p.stack).collect()
+  }
+  // whether there's another hand to play
+  pub fn is_over(&self) -> bool {
+    let human_broke = self
+      .players
+      .iter()
+      .any(|p| matches!(p.seat, Seat::Human) && p.stack == 0);
+    human_broke || self.players.iter().filter(|p| p.stack > 0).count() < 2
+  }
+  // Plays hands until the game is over or the player quits.
+  pub fn run(&mut self) -> io::Result<()> {
+    while !self.is_over() {
+      if !self.play_hand()? {
+        break;
+      }
+    }
+    writeln!(self.output, "\nFinal stacks:")?;
+    for p in &self.players {
+      writeln!(self.output, "  {:<12} {}", p.name, p.stack)?;
+    }
+    Ok(())
+  }
+  // Plays one hand. Returns false if the player quit.
+  pub fn play_hand(&mut self) -> io::Result<bool> {
+    // only players with chips are dealt in
+    let seated: Vec<usize> = (0..self.players.len())
+      .filter(|&i| self.players[i].stack > 0)
+      .collect();
+    let n = seated.len();
+    if n > Permutation::MAX_PLAYERS {
+      let message = "A table has at most 23 players.";
+      return Err(io::Error::new(io::ErrorKind::InvalidInput, message));
+    }
+    let button = (0..n).find(|&s| seated[s] >= self.button).unwrap_or(0);
+    let mut deck = Card::all();
+    self.rng.partial_shuffle(&mut deck, 2 * n + 5);
+    let hole = |s: usize| [deck[2 * s], deck[2 * s + 1]];
+    let board = &deck[2 * n..2 * n + 5];
+    let stacks = seated.iter().map(|&i| self.players[i].stack).collect();
+    let mut table = Table::new(stacks, button, self.small_blind, self.big_blind, 0)
+      .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+
+    writeln!(self.output, "\n=== New hand ===")?;
+    for &(s, amount) in table.forced_bets() {
+      writeln!(
+        self.output,
+        "{} posts {}",
+        self.players[seated[s]].name, amount
+      )?;
+    }
+    let names: Vec<String> = seated
+      .iter()
+      .map(|&i| self.players[i].name.clone())
+      .collect();
+    let mut street = Street::Preflop;
+    while let Some(s) = table.to_act() {
+      if table.street() != street {
+        street = table.street();
+        let shown = &board[..street.board_len()];
+        writeln!(self.output, "--- {}: {} ---", street, cards(shown))?;
+      }
+      let shown = &board[..table.street().board_len()];
+      let view = View::new(&table, hole(s), shown).unwrap();
+      let action = match &mut self.players[seated[s]].seat {
+        Seat::Bot(agent) => agent.act(&view),
+        Seat::Human => match prompt(&mut self.input, &mut self.output, &view, &names)? {
+          Some(action) => action,
+          None => return Ok(false),
+        },
+      };
+      table
+        .apply(action)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+      writeln!(self.output, "{} {}", self.players[seated[s]].name, action)?;
+    }
+
+    let showdown = table.is_showdown();
+    if showdown && table.street().board_len() > street.board_len() {
+      writeln!(self.output, "--- Board: {} ---", cards(board))?;
+    }
+    let ranks: Vec<_> = (0..n)
+      .map(|s| {
+        (showdown && !table.folded()[s])
+          .then(|| Deal::new(hole(s), board.try_into().unwrap()).rank())
+      })
+      .collect();
+    for (s, rank) in ranks.iter().enumerate() {
+      if let Some(rank) = rank {
+        let name = &self.players[seated[s]].name;
+        writeln!(self.output, "{} shows {}: {}", name, cards(&hole(s)), rank)?;
+      }
+    }
+    let payouts = table.payouts(&ranks);
+    for (s, &won) in payouts.iter().enumerate() {
+      let player = &mut self.players[seated[s]];
+      player.stack = table.stacks()[s] + won;
+      if won > 0 {
+        writeln!(self.output, "{} wins {}", player.name, won)?;
+      }
+    }
+    self.button = (seated[button] + 1) % self.players.len();
+    Ok(true)
+  }
+}
+
+// Shows the table to the player and reads an action. None if they quit or
+// the input ran out.
+fn prompt<R: BufRead, W: Write>(
+  input: &mut R,
+  output: &mut W,
+  view: &View,
+  names: &[String],
+) -> io::Result<Option<Action>> {
+  writeln!(output)?;
+  for (s, name) in names.iter().enumerate() {
+    let marker = if s == view.button { "D" } else { " " };
+    let status = if view.folded[s] { " (folded)" } else { "" };
+    writeln!(
+      output,
+      "  {} {:<12} {:>6} in, {:>6} behind{}",
+      marker, name, view.contributions[s], view.stacks[s], status
+    )?;
+  }
+  writeln!(output, "  Pot {}  Board [{}]", view.pot, cards(view.board))?;
+  let hand = match view.rank() {
+    Some(rank) => format!(" ({})", rank),
+    None => String::new(),
+  };
+  writeln!(output, "  You hold {}{}", cards(&view.hole), hand)?;
+  loop {
+    write!(output, "Your action ({}): ", options(&view.legal))?;
+    output.flush()?;
+    let mut line = String::new();
+    if input.read_line(&mut line)? == 0 {
+      return Ok(None);
+    }
+    let line = line.trim();
+    if line.eq_ignore_ascii_case("q") || line.eq_ignore_ascii_case("quit") {
+      return Ok(None);
+    }
+    match parse_action(line, &view.legal) {
+      Ok(action) => return Ok(Some(action)),
+      Err(e) => writeln!(output, "{}", e)?,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::agent::CallingStation;
+  use std::io::Cursor;
+
+  fn legal(to_call: u64) -> Legal {
+    Legal {
+      current_bet: 20,
+      to_call,
+      min_raise: Some(40),
+      max_raise: Some(1000),
+    }
+  }
+
+  #[test]
+  fn test_parse_action() {
+    assert_eq!(parse_action("f", &legal(10)), Ok(Action::Fold));
+    assert!(parse_action("fold", &legal(0)).is_err());
+    assert_eq!(parse_action("Call", &legal(10)), Ok(Action::Call(10)));
+    assert_eq!(parse_action("k", &legal(0)), Ok(Action::Check));
+    assert_eq!(parse_action("r 60", &legal(10)), Ok(Action::Raise(60)));
+    assert_eq!(parse_action("allin", &legal(10)), Ok(Action::Raise(1000)));
+    assert!(parse_action("raise 30", &legal(10)).is_err());
+    assert!(parse_action("raise lots", &legal(10)).is_err());
+    assert!(parse_action("dance", &legal(10)).is_err());
+  }
+  #[test]
+  fn test_options() {
+    assert_eq!(options(&legal(10)), "fold, call 10, raise 40-1000");
+  }
+  #[test]
+  fn test_game() {
+    // the human calls every time, so every hand goes to showdown
+    let input = Cursor::new("nonsense\ncall\n".to_string() + &"c\n".repeat(500));
+    let mut output = Vec::new();
+    let mut game = Game::new(1, 2, 9, input, &mut output);
+    game.sit("You", Seat::Human, 100);
+    game.sit("Station", Seat::Bot(Box::new(CallingStation)), 100);
+    for _ in 0..5 {
+      assert!(game.play_hand().unwrap());
+    }
+    assert_eq!(game.stacks().iter().sum::<u64>(), 200);
+    let text = String::from_utf8(output).unwrap();
+    assert!(text.contains("You hold"));
+    assert!(text.contains("Type fold"));
+    assert!(text.contains("Station shows"));
+    assert!(text.contains("--- River"));
+  }
+  #[test]
+  fn test_quit() {
+    let mut output = Vec::new();
+    let mut game = Game::new(1, 2, 1, Cursor::new("quit\n"), &mut output);
+    game.sit("You", Seat::Human, 100);
+    game.sit("Station", Seat::Bot(Box::new(CallingStation)), 100);
+    game.run().unwrap();
+    let text = String::from_utf8(output).unwrap();
+    assert!(text.contains("Final stacks"));
+  }
+}
@@ -0,0 +1,333 @@
+// Grading of permutation test cases: a solver is given nine card numbers and
+// returns the cards of the winning hand, as strings like "10S" or "1H".
+//
+// Case files have one case per line, the nine card numbers, a colon, the
+// expected cards, and optionally a # and a name:
+//   40 41 42 43 48 49 50 51 52: 10S 11S 12S 13S 1S  # Royal flush
+// Blank lines and lines starting with # are skipped.
+//
+// A case scores the share of the expected cards the solver returned, or
+// nothing if it returned more than five cards.
+
+use crate::hand::grader_string;
+use crate::Card;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Case {
+  pub name: String,
+  pub perm: [u32; 9],
+  pub expected: Vec<String>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseError {
+  pub line: usize,
+  pub message: &'static str,
+}
+
+impl fmt::Display for ParseError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "Line {}: {}", self.line, self.message)
+  }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Outcome {
+  pub case: Case,
+  pub returned: Vec<String>,
+  // expected cards the solver left out, and cards it shouldn't have returned
+  pub missing: Vec<String>,
+  pub extra: Vec<String>,
+  // from 0 to 1
+  pub score: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Report {
+  pub outcomes: Vec<Outcome>,
+}
+
+// a case from one line, with an empty name if it wasn't given one
+fn parse_line(line: &str) -> Result<Option<Case>, &'static str> {
+  let (line, name) = match line.split_once('#') {
+    Some((line, name)) => (line.trim(), name.trim().to_string()),
+    None => (line.trim(), String::new()),
+  };
+  if line.is_empty() {
+    return Ok(None);
+  }
+  let (perm, expected) = line
+    .split_once(':')
+    .ok_or("Expected cards must follow a colon.")?;
+  let perm: Vec<u32> = perm
+    .split(|c: char| c.is_whitespace() || c == ',')
+    .filter(|s| !s.is_empty())
+    .map(|s| s.parse().ok().filter(|n| (1..=52).contains(n)))
+    .collect::<Option<_>>()
+    .ok_or("Card numbers must be from 1 to 52.")?;
+  let perm: [u32; 9] = perm
+    .try_into()
+    .map_err(|_| "A permutation has nine cards.")?;
+  if (1..9).any(|i| perm[..i].contains(&perm[i])) {
+    return Err("A card is repeated.");
+  }
+  let expected: Vec<String> = expected.split_whitespace().map(|s| s.to_string()).collect();
+  if expected.is_empty() {
+    return Err("No expected cards.");
+  }
+  Ok(Some(Case {
+    name,
+    perm,
+    expected,
+  }))
+}
+
+pub fn parse_cases(text: &str) -> Result<Vec<Case>, ParseError> {
+  let mut cases = Vec::new();
+  for (i, line) in text.lines().enumerate() {
+    if line.trim_start().starts_with('#') {
+      continue;
+    }
+    match parse_line(line) {
+      Ok(Some(mut case)) => {
+        if case.name.is_empty() {
+          case.name = format!("Test {}", cases.len() + 1);
+        }
+        cases.push(case);
+      }
+      Ok(None) => {}
+      Err(message) => {
+        return Err(ParseError {
+          line: i + 1,
+          message,
+        })
+      }
+    }
+  }
+  Ok(cases)
+}
+
+pub fn load(path: &Path) -> Result<Vec<Case>, String> {
+  let text = fs::read_to_string(path).map_err(|_| format!("Could not read {}.", path.display()))?;
+  parse_cases(&text).map_err(|e| e.to_string())
+}
+
+pub fn grade(cases: &[Case], solve: impl Fn([u32; 9]) -> Vec<String>) -> Report {
+  let outcomes = cases
+    .iter()
+    .map(|case| {
+      let returned = solve(case.perm);
+      let missing: Vec<String> = case
+        .expected
+        .iter()
+        .filter(|c| !returned.contains(c))
+        .cloned()
+        .collect();
+      let extra: Vec<String> = returned
+        .iter()
+        .filter(|c| !case.expected.contains(c))
+        .cloned()
+        .collect();
+      let found = case.expected.len() - missing.len();
+      let score = if returned.len() > 5 {
+        0.0
+      } else {
+        found as f64 / case.expected.len() as f64
+      };
+      Outcome {
+        case: case.clone(),
+        returned,
+        missing,
+        extra,
+        score,
+      }
+    })
+    .collect();
+  Report { outcomes }
+}
+
+impl Outcome {
+  pub fn is_full_marks(&self) -> bool {
+    self.score == 1.0
+  }
+  // the cards each player and the pool were dealt, in the grader's format
+  pub fn dealt(&self) -> [String; 3] {
+    let card = |i: usize| grader_string(&Card::from(self.case.perm[i]));
+    [
+      format!("{},{}", card(0), card(2)),
+      format!("{},{}", card(1), card(3)),
+      (4..9).map(card).collect::<Vec<_>>().join(","),
+    ]
+  }
+  fn summary(&self) -> String {
+    let found = self.case.expected.len() - self.missing.len();
+    if self.returned.len() > 5 {
+      "Returned more than five cards!".to_string()
+    } else {
+      format!("{} of {} cards correct", found, self.case.expected.len())
+    }
+  }
+}
+
+fn xml_escape(s: &str) -> String {
+  s.replace('&', "&amp;")
+    .replace('<', "&lt;")
+    .replace('>', "&gt;")
+    .replace('"', "&quot;")
+}
+
+impl Report {
+  pub fn total(&self) -> f64 {
+    self.outcomes.iter().map(|o| o.score).sum()
+  }
+  pub fn percent(&self) -> f64 {
+    100.0 * self.total() / self.outcomes.len().max(1) as f64
+  }
+  pub fn failures(&self) -> usize {
+    self.outcomes.iter().filter(|o| !o.is_full_marks()).count()
+  }
+  // JUnit XML, one testcase per case, failing unless it got full marks
+  pub fn junit(&self, suite: &str) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml += &format!(
+      "<testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"0\">\n",
+      xml_escape(suite),
+      self.outcomes.len(),
+      self.failures()
+    );
+    for outcome in &self.outcomes {
+      let name = xml_escape(&outcome.case.name);
+      if outcome.is_full_marks() {
+        xml += &format!(
+          "  <testcase classname=\"{}\" name=\"{}\"/>\n",
+          xml_escape(suite),
+          name
+        );
+        continue;
+      }
+      xml += &format!(
+        "  <testcase classname=\"{}\" name=\"{}\">\n",
+        xml_escape(suite),
+        name
+      );
+      xml += &format!(
+        "    <failure message=\"{}\">perm {:?}\nreturned: {}\nmissing: {}\nextra: {}</failure>\n",
+        xml_escape(&outcome.summary()),
+        outcome.case.perm,
+        xml_escape(&outcome.returned.join(" ")),
+        xml_escape(&outcome.missing.join(" ")),
+        xml_escape(&outcome.extra.join(" "))
+      );
+      xml += "  </testcase>\n";
+    }
+    xml += "</testsuite>\n";
+    xml
+  }
+}
+
+impl fmt::Display for Report {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for (i, outcome) in self.outcomes.iter().enumerate() {
+      let c = outcome.case.expected.len();
+      if outcome.is_full_marks() {
+        writeln!(
+          f,
+          "Test {} FULL MARKS  ({} of {} cards correct)",
+          i + 1,
+          c,
+          c
+        )?;
+        continue;
+      }
+      let [p1, p2, pool] = outcome.dealt();
+      writeln!(
+        f,
+        "Test {} DISCREPANCY: {} {:?}",
+        i + 1,
+        outcome.case.name,
+        outcome.case.perm
+      )?;
+      writeln!(f, "  P1:   {}", p1)?;
+      writeln!(f, "  P2:   {}", p2)?;
+      writeln!(f, "  Pool: {}", pool)?;
+      writeln!(f, "  You returned:   {:?}", outcome.returned)?;
+      writeln!(f, "  Should contain: {:?}", outcome.case.expected)?;
+      writeln!(
+        f,
+        "  Missing: {:?}  Extra: {:?}",
+        outcome.missing, outcome.extra
+      )?;
+      writeln!(f, "  {}", outcome.summary())?;
+    }
+    write!(
+      f,
+      "\nTotal score: {:.1}% ({:.1}/{} points)",
+      self.percent(),
+      self.total(),
+      self.outcomes.len()
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::poker;
+
+  const CASES: &str = include_str!("../cases/permutations.txt");
+
+  #[test]
+  fn test_parse_cases() {
+    let cases = parse_cases(CASES).unwrap();
+    assert_eq!(cases.len(), 10);
+    assert_eq!(cases[1].name, "Royal flush vs straight flush");
+    assert_eq!(cases[9].expected, vec!["1H", "1S"]);
+    let unnamed = parse_cases("\n1 2 3 4 5 6 7 8 9: 1C\n").unwrap();
+    assert_eq!(unnamed[0].name, "Test 1");
+    let err = parse_cases("# header\n1 2 3 4 5 6 7 8: 1C\n").unwrap_err();
+    assert_eq!(err.line, 2);
+    assert!(parse_cases("1 2 3 4 5 6 7 8 9\n").is_err());
+    assert!(parse_cases("1 2 3 4 5 6 7 8 53: 1C\n").is_err());
+    assert!(parse_cases("1 1 3 4 5 6 7 8 9: 1C\n").is_err());
+  }
+  #[test]
+  fn test_grade() {
+    let cases = parse_cases(CASES).unwrap();
+    let report = grade(&cases, poker::deal);
+    assert_eq!(report.failures(), 0);
+    assert_eq!(report.percent(), 100.0);
+    assert!(report
+      .to_string()
+      .ends_with("Total score: 100.0% (10.0/10 points)"));
+
+    // a solver that gets half of a two card answer and overshoots another
+    let report = grade(&cases[8..], |perm| {
+      if perm[0] == 40 {
+        vec!["1H".to_string(), "2C".to_string()]
+      } else {
+        (1..=6).map(|n| format!("{}C", n)).collect()
+      }
+    });
+    assert_eq!(report.outcomes[0].score, 0.0);
+    assert_eq!(report.outcomes[1].score, 0.5);
+    assert_eq!(report.outcomes[1].missing, vec!["1S"]);
+    assert_eq!(report.outcomes[1].extra, vec!["2C"]);
+    let text = report.to_string();
+    assert!(text.contains("Returned more than five cards!"), "{}", text);
+    assert!(text.contains("  P1:   1S,7S"), "{}", text);
+  }
+  #[test]
+  fn test_junit() {
+    let cases = parse_cases(CASES).unwrap();
+    let report = grade(&cases[..2], |perm| poker::deal(perm)[..1].to_vec());
+    let xml = report.junit("poker <perms>");
+    assert!(xml.contains("tests=\"2\" failures=\"2\""), "{}", xml);
+    assert!(xml.contains("name=\"poker &lt;perms&gt;\""));
+    assert_eq!(xml.matches("<failure").count(), 2);
+    let passing = grade(&cases[..2], poker::deal).junit("ok");
+    assert!(!passing.contains("<failure"));
+  }
+}
@@ -0,0 +1,224 @@
+use crate::Face;
+use crate::HandClass;
+use crate::Range;
+use std::fmt;
+use std::str::FromStr;
+
+// The usual 13x13 chart of preflop hands, Aces in the top left. Pairs run
+// down the diagonal, suited hands sit above it and offsuit hands below, so
+// the row is the high card for suited hands and the column for offsuit ones.
+// Each cell holds the weight of its class, 0 to 1.
+#[derive(Clone, Debug, Copy, Default, PartialEq)]
+pub struct Grid {
+  weights: [[f64; 13]; 13],
+}
+
+// row or column of a face, Ace first
+fn position(face: Face) -> usize {
+  12 - face as usize
+}
+
+fn face_at(position: usize) -> Face {
+  Face::all()[12 - position]
+}
+
+impl Grid {
+  pub fn new() -> Self {
+    Self::default()
+  }
+  // the class drawn in a cell
+  pub fn class(row: Face, col: Face) -> HandClass {
+    HandClass::new(row, col, position(row) < position(col))
+  }
+  // the cell a class is drawn in, as (row, column)
+  pub fn cell(class: &HandClass) -> (Face, Face) {
+    if class.is_suited() {
+      (class.high(), class.low())
+    } else {
+      (class.low(), class.high())
+    }
+  }
+  pub fn get(&self, row: Face, col: Face) -> f64 {
+    self.weights[position(row)][position(col)]
+  }
+  pub fn set(&mut self, row: Face, col: Face, weight: f64) {
+    self.weights[position(row)][position(col)] = weight.clamp(0.0, 1.0);
+  }
+  pub fn weight(&self, class: &HandClass) -> f64 {
+    let (row, col) = Self::cell(class);
+    self.get(row, col)
+  }
+  pub fn set_weight(&mut self, class: &HandClass, weight: f64) {
+    let (row, col) = Self::cell(class);
+    self.set(row, col, weight);
+  }
+  // the weighted share of all 1326 combos
+  pub fn coverage(&self) -> f64 {
+    let combos: f64 = HandClass::all()
+      .iter()
+      .map(|class| self.weight(class) * class.combos().len() as f64)
+      .sum();
+    combos / 1326.0
+  }
+  // Every combo of a class gets the class weight.
+  pub fn to_range(&self) -> Range {
+    let mut range = Range::new();
+    for class in HandClass::all() {
+      let weight = self.weight(&class);
+      for combo in class.combos() {
+        range.insert(combo, weight);
+      }
+    }
+    range
+  }
+  // Hand labels for the cells in the range, with a '~' after partly weighted
+  // ones and dots for the rest. With ansi set, cells are coloured instead:
+  // green when fully in the range and yellow when partly.
+  pub fn render(&self, ansi: bool) -> String {
+    let mut out = String::new();
+    for row in 0..13 {
+      let cells: Vec<String> = (0..13)
+        .map(|col| {
+          let weight = self.weights[row][col];
+          let label = Self::class(face_at(row), face_at(col)).to_string();
+          match (ansi, weight) {
+            (false, 0.0) => "  . ".to_string(),
+            (false, w) if w < 1.0 => format!("{:<3}~", label),
+            (false, _) => format!("{:<3} ", label),
+            (true, 0.0) => format!("\x1b[2m{:<3}\x1b[0m", label),
+            (true, w) if w < 1.0 => format!("\x1b[30;43m{:<3}\x1b[0m", label),
+            (true, _) => format!("\x1b[30;42m{:<3}\x1b[0m", label),
+          }
+        })
+        .collect();
+      let line = cells.join(if ansi { " " } else { "" });
+      out.push_str(line.trim_end());
+      out.push('\n');
+    }
+    out
+  }
+}
+
+// Each class takes the average weight of its combos in the range.
+impl From<&Range> for Grid {
+  fn from(range: &Range) -> Self {
+    let mut grid = Self::new();
+    for class in HandClass::all() {
+      let combos = class.combos();
+      let total: f64 = combos.iter().map(|c| range.weight(c)).sum();
+      grid.set_weight(&class, total / combos.len() as f64);
+    }
+    grid
+  }
+}
+
+impl From<&Grid> for Range {
+  fn from(grid: &Grid) -> Self {
+    grid.to_range()
+  }
+}
+
+// Thirteen lines of thirteen weights, Aces first.
+impl fmt::Display for Grid {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for row in self.weights.iter() {
+      let cells: Vec<String> = row.iter().map(|w| w.to_string()).collect();
+      writeln!(f, "{}", cells.join(" "))?;
+    }
+    Ok(())
+  }
+}
+
+// Reads the weight table written by Display, where a dash or dot also means
+// 0, or else a range string such as "TT+, AKs".
+impl FromStr for Grid {
+  type Err = &'static str;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let rows: Vec<&str> = s.lines().filter(|l| !l.trim().is_empty()).collect();
+    if rows.len() != 13 {
+      let range: Range = s.parse()?;
+      return Ok(Self::from(&range));
+    }
+    let mut grid = Self::new();
+    for (r, line) in rows.iter().enumerate() {
+      let cells: Vec<&str> = line.split_whitespace().collect();
+      if cells.len() != 13 {
+        return Err("Grid rows need 13 cells.");
+      }
+      for (c, cell) in cells.iter().enumerate() {
+        let weight = match *cell {
+          "-" | "." => 0.0,
+          cell => cell.parse().map_err(|_| "Invalid weight.")?,
+        };
+        if !(0.0..=1.0).contains(&weight) {
+          return Err("Weights must be between 0 and 1.");
+        }
+        grid.weights[r][c] = weight;
+      }
+    }
+    Ok(grid)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_layout() {
+    assert_eq!(Grid::class(Face::Ace, Face::Ace).to_string(), "AA");
+    assert_eq!(Grid::class(Face::Ace, Face::King).to_string(), "AKs");
+    assert_eq!(Grid::class(Face::King, Face::Ace).to_string(), "AKo");
+    assert_eq!(Grid::class(Face::Two, Face::Three).to_string(), "32o");
+    for class in HandClass::all() {
+      let (row, col) = Grid::cell(&class);
+      assert_eq!(Grid::class(row, col), class);
+    }
+  }
+  #[test]
+  fn test_range_round_trip() {
+    let range: Range = "TT+, 55:0.5, AJs+, KQo".parse().unwrap();
+    let grid = Grid::from(&range);
+    assert_eq!(grid.get(Face::Ace, Face::King), 1.0);
+    assert_eq!(grid.get(Face::King, Face::Ace), 0.0);
+    assert_eq!(grid.get(Face::Queen, Face::King), 1.0);
+    assert_eq!(grid.get(Face::Five, Face::Five), 0.5);
+    assert_eq!(grid.to_range(), range);
+    assert_eq!(grid.to_range().to_string(), "TT+, 55:0.5, AJs+, KQo");
+    assert_eq!("TT+, 55:0.5, AJs+, KQo".parse::<Grid>().unwrap(), grid);
+  }
+  #[test]
+  fn test_partial_class() {
+    // one suited combo of four
+    let grid = Grid::from(&"AhKh".parse::<Range>().unwrap());
+    assert_eq!(grid.weight(&"AKs".parse().unwrap()), 0.25);
+    assert!((grid.coverage() - 1.0 / 1326.0).abs() < 1e-12);
+  }
+  #[test]
+  fn test_table_round_trip() {
+    let grid: Grid = "22+, A2s+, K9o+".parse().unwrap();
+    assert_eq!(grid.to_string().parse::<Grid>().unwrap(), grid);
+    let text = grid.to_string().replace('0', "-").replace('1', "1.0");
+    assert_eq!(text.parse::<Grid>().unwrap(), grid);
+    let short = grid
+      .to_string()
+      .lines()
+      .skip(1)
+      .collect::<Vec<_>>()
+      .join("\n");
+    assert!(short.parse::<Grid>().is_err());
+    assert!(grid.to_string().replace('1', "2").parse::<Grid>().is_err());
+  }
+  #[test]
+  fn test_render() {
+    let grid: Grid = "AA, AKs:0.5".parse().unwrap();
+    let text = grid.render(false);
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines.len(), 13);
+    assert!(lines[0].starts_with("AA  AKs~  . "));
+    assert_eq!(lines[12], format!("  .{}", "   .".repeat(12)));
+    let ansi = grid.render(true);
+    assert!(ansi.starts_with("\x1b[30;42mAA \x1b[0m \x1b[30;43mAKs\x1b[0m"));
+  }
+}
@@ -6,6 +6,13 @@ use std::cmp::Ordering;
 use std::fmt;
 use std::str::FromStr;
 
+// face counts (most frequent first), suit counts and whether it's a straight
+pub type Counts = ([(u8, Face); 5], [(u8, Suit); 4], bool);
+
+pub(crate) fn grader_string(card: &Card) -> String {
+  format!("{}{}", card.face().number(), card.suit())
+}
+
 #[derive(Clone, Debug, Copy, Eq, PartialEq)]
 pub struct Hand {
   cards: [Card; 5],
@@ -13,7 +20,7 @@ pub struct Hand {
 
 impl PartialOrd for Hand {
   fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-    self.rank().partial_cmp(&other.rank())
+    Some(self.cmp(other))
   }
 }
 
@@ -42,13 +49,9 @@ impl Hand {
     indexes.map(|c| c - indexes[4])
   }
   pub fn is_straight(&self) -> bool {
-    match self.face_offsets() {
-      [12, 3, 2, 1, 0] => true,
-      [4, 3, 2, 1, 0] => true,
-      _ => false,
-    }
+    matches!(self.face_offsets(), [12, 3, 2, 1, 0] | [4, 3, 2, 1, 0])
   }
-  pub fn counts(&self) -> ([(u8, Face); 5], [(u8, Suit); 4], bool) {
+  pub fn counts(&self) -> Counts {
     let mut faces = Face::counters();
     let mut suits = Suit::counters();
     for card in &self.cards {
@@ -59,16 +62,43 @@ impl Hand {
     faces.reverse();
     suits.sort();
     suits.reverse();
+    let mut faces: [(u8, Face); 5] = faces[0..5].try_into().unwrap();
+    // the ace plays low in a wheel
     if faces[0].1 == Face::Ace && self.is_straight() && faces[1].1 != Face::King {
       faces.rotate_left(1);
     }
-    (faces[0..5].try_into().unwrap(), suits, self.is_straight())
+    (faces, suits, self.is_straight())
   }
   pub fn rank(&self) -> Rank {
     self.into()
   }
+  // All five cards, most significant first: the cards that make the hand
+  // (both pairs of a two pair, the wheel from Five down to Ace) followed by
+  // the kickers.
+  pub fn ordered(&self) -> [Card; 5] {
+    let mut remaining = self.cards.to_vec();
+    self.rank().faces().map(|face| {
+      let i = remaining.iter().position(|c| c.face() == face).unwrap();
+      remaining.remove(i)
+    })
+  }
+  // the cards that make the hand and the kickers
+  pub fn split(&self) -> (Vec<Card>, Vec<Card>) {
+    let ordered = self.ordered();
+    let (made, kickers) = ordered.split_at(self.rank().made());
+    (made.to_vec(), kickers.to_vec())
+  }
+  pub fn made(&self) -> Vec<Card> {
+    self.split().0
+  }
+  pub fn kickers(&self) -> Vec<Card> {
+    self.split().1
+  }
   pub fn as_str_vec(&self) -> Vec<String> {
-    self.cards.map(|c| c.to_string()).to_vec()
+    self.cards.iter().map(grader_string).collect()
+  }
+  pub fn made_str_vec(&self) -> Vec<String> {
+    self.made().iter().map(grader_string).collect()
   }
 }
 
@@ -77,11 +107,11 @@ impl FromStr for Hand {
 
   fn from_str(s: &str) -> Result<Self, Self::Err> {
     let mut cards = [
-      (&s[0..2]).parse().unwrap(),
-      (&s[3..5]).parse().unwrap(),
-      (&s[6..8]).parse().unwrap(),
-      (&s[9..11]).parse().unwrap(),
-      (&s[12..14]).parse().unwrap(),
+      s[0..2].parse().unwrap(),
+      s[3..5].parse().unwrap(),
+      s[6..8].parse().unwrap(),
+      s[9..11].parse().unwrap(),
+      s[12..14].parse().unwrap(),
     ];
     cards.sort();
     cards.reverse();
@@ -188,11 +218,11 @@ mod tests {
       hand.counts(),
       (
         [
-          (1, Face::Ace),
           (1, Face::Five),
           (1, Face::Four),
           (1, Face::Three),
           (1, Face::Two),
+          (1, Face::Ace),
         ],
         [
           (2, Suit::Hearts),
@@ -205,6 +235,38 @@ mod tests {
     );
   }
   #[test]
+  fn test_split() {
+    let split = |s: &str| {
+      let (made, kickers) = s.parse::<Hand>().unwrap().split();
+      let join = |cards: Vec<Card>| {
+        cards
+          .iter()
+          .map(|c| c.to_string())
+          .collect::<Vec<String>>()
+          .join(" ")
+      };
+      (join(made), join(kickers))
+    };
+    assert_eq!(split("AH AD AC AS KH"), ("AS AH AD AC".into(), "KH".into()));
+    assert_eq!(split("7H AD KD 7S AH"), ("AH AD 7S 7H".into(), "KD".into()));
+    assert_eq!(split("AH AD JD 9S 2H"), ("AH AD".into(), "JD 9S 2H".into()));
+    assert_eq!(split("QS 3H KC 8D 2H"), ("KC".into(), "QS 8D 3H 2H".into()));
+    assert_eq!(
+      split("4H AD 3D 2S 5H"),
+      ("5H 4H 3D 2S AD".into(), "".into())
+    );
+    assert_eq!(
+      split("QS QH KC QD KH"),
+      ("QS QH QD KH KC".into(), "".into())
+    );
+  }
+  #[test]
+  fn test_made_str_vec() {
+    let hand: Hand = "AH AD JD 9S 2H".parse().unwrap();
+    assert_eq!(hand.made_str_vec(), vec!["1H", "1D"]);
+    assert_eq!(hand.as_str_vec(), vec!["1H", "1D", "11D", "9S", "2H"]);
+  }
+  #[test]
   fn test_display() {
     let hand: Hand = "AH AD AC KS QH".parse().unwrap();
     assert_eq!(hand.to_string(), "AH AD AC KS QH");
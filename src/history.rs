@@ -0,0 +1,927 @@
+use crate::Action;
+use crate::Card;
+use crate::Deal;
+use crate::Rank;
+use crate::Street;
+use crate::Strings;
+use crate::Table;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Seat {
+  pub number: u32,
+  pub name: String,
+  pub stack: u64,
+  pub hole: Option<[Card; 2]>,
+}
+
+#[derive(Clone, Debug, Copy, Eq, PartialEq)]
+pub enum Post {
+  SmallBlind,
+  BigBlind,
+  Ante,
+}
+
+// Players are referred to by their index into HandHistory::seats.
+#[derive(Clone, Debug, Copy, Eq, PartialEq)]
+pub struct ForcedBet {
+  pub seat: usize,
+  pub post: Post,
+  pub amount: u64,
+}
+
+#[derive(Clone, Debug, Copy, Eq, PartialEq)]
+pub struct Play {
+  pub street: Street,
+  pub seat: usize,
+  pub action: Action,
+}
+
+#[derive(Clone, Debug, Copy, Eq, PartialEq)]
+pub struct Collected {
+  pub seat: usize,
+  pub amount: u64,
+}
+
+// A completed hand of no limit hold'em.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct HandHistory {
+  pub id: u64,
+  pub table: String,
+  pub max_seats: u32,
+  // seconds since the Unix epoch, UTC
+  pub timestamp: u64,
+  pub small_blind: u64,
+  pub big_blind: u64,
+  pub button: usize,
+  pub seats: Vec<Seat>,
+  pub forced_bets: Vec<ForcedBet>,
+  pub actions: Vec<Play>,
+  pub board: Vec<Card>,
+  pub collected: Vec<Collected>,
+}
+
+impl HandHistory {
+  // the street each player folded on, if they did
+  pub fn folds(&self) -> Vec<Option<Street>> {
+    let mut folds = vec![None; self.seats.len()];
+    for play in &self.actions {
+      if play.action == Action::Fold {
+        folds[play.seat] = Some(play.street);
+      }
+    }
+    folds
+  }
+  // players still in the hand when the cards are shown
+  pub fn showdown(&self) -> Vec<usize> {
+    let remaining: Vec<usize> = self
+      .folds()
+      .iter()
+      .enumerate()
+      .filter(|(_, fold)| fold.is_none())
+      .map(|(seat, _)| seat)
+      .collect();
+    if remaining.len() > 1 {
+      remaining
+    } else {
+      Vec::new()
+    }
+  }
+  pub fn deal(&self, seat: usize) -> Option<Deal> {
+    let community = self.board.as_slice().try_into().ok()?;
+    Some(Deal::new(self.seats[seat].hole?, community))
+  }
+  pub fn rank(&self, seat: usize) -> Option<Rank> {
+    self.deal(seat).map(|deal| deal.rank())
+  }
+  pub fn collected(&self, seat: usize) -> u64 {
+    self
+      .collected
+      .iter()
+      .filter(|c| c.seat == seat)
+      .map(|c| c.amount)
+      .sum()
+  }
+  // Chips each player put in the pot, with uncalled bets already returned.
+  pub fn contributions(&self) -> Vec<u64> {
+    let mut ledger = Ledger::new(self);
+    for play in &self.actions {
+      ledger.advance(play.street);
+      ledger.apply(play);
+    }
+    ledger.close_street();
+    ledger.total
+  }
+  pub fn total_pot(&self) -> u64 {
+    self.contributions().iter().sum()
+  }
+  // Checks the history is consistent enough to write out: seats exist, the
+  // board has reached every street with actions on it, and raises are to
+  // at least the current bet. Replay checks the rest of the rules.
+  pub fn check(&self) -> Result<(), &'static str> {
+    let n = self.seats.len();
+    if self.button >= n {
+      return Err("Button is not at a seat.");
+    }
+    let seats = self.forced_bets.iter().map(|b| b.seat);
+    let seats = seats.chain(self.actions.iter().map(|p| p.seat));
+    if seats
+      .chain(self.collected.iter().map(|c| c.seat))
+      .any(|s| s >= n)
+    {
+      return Err("Player is not at a seat.");
+    }
+    if ![0, 3, 4, 5].contains(&self.board.len()) {
+      return Err("Board does not match the streets played.");
+    }
+    let mut ledger = Ledger::new(self);
+    for play in &self.actions {
+      if play.street.board_len() > self.board.len() {
+        return Err("Action on a street the board hasn't reached.");
+      }
+      if play.street < ledger.street {
+        return Err("Action recorded on the wrong street.");
+      }
+      ledger.advance(play.street);
+      if let Action::Raise(to) = play.action {
+        if to < ledger.current_bet() {
+          return Err("Raise is below the current bet.");
+        }
+      }
+      ledger.apply(play);
+    }
+    Ok(())
+  }
+  // The PokerStars text for the hand, once it passes check. Display writes
+  // the same text without checking, for hands that are known to be sound.
+  pub fn to_text(&self) -> Result<String, &'static str> {
+    self.check()?;
+    Ok(self.to_string())
+  }
+  // Plays the hand again on a Table, checking that every action was legal
+  // and that the chips went to the best hands. Returns what each player
+  // should have collected.
+  pub fn replay(&self) -> Result<Vec<u64>, &'static str> {
+    if self.seats.len() < 2 {
+      return Err("A hand needs at least two players.");
+    }
+    self.check()?;
+    let blind = |post: Post| {
+      self
+        .forced_bets
+        .iter()
+        .find(|b| b.post == post)
+        .map_or(0, |b| b.amount)
+    };
+    let mut table = Table::new(
+      self.seats.iter().map(|s| s.stack).collect(),
+      self.button,
+      blind(Post::SmallBlind),
+      blind(Post::BigBlind),
+      blind(Post::Ante),
+    )?;
+    let mut posted: Vec<(usize, u64)> = self
+      .forced_bets
+      .iter()
+      .map(|b| (b.seat, b.amount.min(self.seats[b.seat].stack)))
+      .collect();
+    let mut expected = table.forced_bets().to_vec();
+    posted.sort();
+    expected.sort();
+    if posted != expected {
+      return Err("Forced bets do not match the button.");
+    }
+    for play in &self.actions {
+      if table.street() != play.street {
+        return Err("Action recorded on the wrong street.");
+      }
+      if table.to_act() != Some(play.seat) {
+        return Err("Player acted out of turn.");
+      }
+      table.apply(play.action)?;
+    }
+    if !table.is_finished() {
+      return Err("Hand ends before the betting is complete.");
+    }
+    if self.board.len() != table.street().board_len() {
+      return Err("Board does not match the streets played.");
+    }
+    let ranks: Vec<Option<Rank>> = (0..self.seats.len()).map(|s| self.rank(s)).collect();
+    if table.is_showdown() && table.live().iter().all(|&s| ranks[s].is_none()) {
+      return Err("Nobody showed a hand at showdown.");
+    }
+    let payouts = table.payouts(&ranks);
+    let recorded: Vec<u64> = (0..self.seats.len()).map(|s| self.collected(s)).collect();
+    if payouts != recorded {
+      return Err("Recorded winnings do not match the best hands.");
+    }
+    Ok(payouts)
+  }
+}
+
+// Tracks stacks and bets while walking through the actions.
+struct Ledger {
+  street: Street,
+  stacks: Vec<u64>,
+  street_in: Vec<u64>,
+  total: Vec<u64>,
+  uncalled: Option<(usize, u64)>,
+}
+
+impl Ledger {
+  fn new(history: &HandHistory) -> Self {
+    let n = history.seats.len();
+    let mut ledger = Self {
+      street: Street::Preflop,
+      stacks: history.seats.iter().map(|s| s.stack).collect(),
+      street_in: vec![0; n],
+      total: vec![0; n],
+      uncalled: None,
+    };
+    for post in &history.forced_bets {
+      let amount = post.amount.min(ledger.stacks[post.seat]);
+      ledger.stacks[post.seat] -= amount;
+      ledger.total[post.seat] += amount;
+      if post.post != Post::Ante {
+        ledger.street_in[post.seat] += amount;
+      }
+    }
+    ledger
+  }
+  fn current_bet(&self) -> u64 {
+    self.street_in.iter().copied().max().unwrap_or(0)
+  }
+  fn apply(&mut self, play: &Play) {
+    let added = match play.action {
+      Action::Fold | Action::Check => 0,
+      Action::Call(amount) | Action::Bet(amount) => amount,
+      Action::Raise(to) => to.saturating_sub(self.street_in[play.seat]),
+    };
+    let added = added.min(self.stacks[play.seat]);
+    self.stacks[play.seat] -= added;
+    self.street_in[play.seat] += added;
+    self.total[play.seat] += added;
+  }
+  // Returns the part of the largest bet nobody matched.
+  fn close_street(&mut self) {
+    let mut order: Vec<usize> = (0..self.street_in.len()).collect();
+    order.sort_by_key(|&seat| std::cmp::Reverse(self.street_in[seat]));
+    self.uncalled = None;
+    if let [top, second, ..] = order[..] {
+      let excess = self.street_in[top] - self.street_in[second];
+      if excess > 0 {
+        self.stacks[top] += excess;
+        self.total[top] -= excess;
+        self.uncalled = Some((top, excess));
+      }
+    }
+    self.street_in.iter_mut().for_each(|s| *s = 0);
+  }
+  fn advance(&mut self, street: Street) -> Vec<Street> {
+    let mut opened = Vec::new();
+    while self.street < street {
+      self.close_street();
+      self.street = self.street.next().unwrap();
+      opened.push(self.street);
+    }
+    opened
+  }
+}
+
+// yyyy/mm/dd hh:mm:ss for a Unix timestamp
+pub fn format_time(timestamp: u64) -> String {
+  let days = (timestamp / 86400) as i64 + 719468;
+  let secs = timestamp % 86400;
+  let era = days.div_euclid(146097);
+  let doe = days - era * 146097;
+  let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+  let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+  let mp = (5 * doy + 2) / 153;
+  let day = doy - (153 * mp + 2) / 5 + 1;
+  let month = if mp < 10 { mp + 3 } else { mp - 9 };
+  let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+  format!(
+    "{}/{:02}/{:02} {:02}:{:02}:{:02}",
+    year,
+    month,
+    day,
+    secs / 3600,
+    secs / 60 % 60,
+    secs % 60
+  )
+}
+
+// inverse of format_time
+pub fn parse_time(time: &str) -> Result<u64, &'static str> {
+  let invalid = "Invalid time.";
+  let (date, clock) = time.trim().split_once(' ').ok_or(invalid)?;
+  let date: Vec<i64> = date
+    .split('/')
+    .map(|p| p.parse().map_err(|_| invalid))
+    .collect::<Result<_, _>>()?;
+  let clock: Vec<u64> = clock
+    .split(':')
+    .map(|p| p.parse().map_err(|_| invalid))
+    .collect::<Result<_, _>>()?;
+  let (&[year, month, day], &[hours, minutes, seconds]) = (&date[..], &clock[..]) else {
+    return Err(invalid);
+  };
+  if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hours > 23 || minutes > 59 {
+    return Err(invalid);
+  }
+  let year = if month <= 2 { year - 1 } else { year };
+  let era = year.div_euclid(400);
+  let yoe = year - era * 400;
+  let doy = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+  let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+  let days = era * 146097 + doe - 719468;
+  if days < 0 {
+    return Err(invalid);
+  }
+  Ok(days as u64 * 86400 + hours * 3600 + minutes * 60 + seconds)
+}
+
+fn cards(cards: &[Card]) -> String {
+  cards
+    .iter()
+    .map(|c| c.to_string())
+    .collect::<Vec<String>>()
+    .join(" ")
+}
+
+fn street_header(street: Street, board: &[Card]) -> String {
+  match street {
+    Street::Preflop => "*** HOLE CARDS ***".to_string(),
+    Street::Flop => format!("*** FLOP *** [{}]", cards(&board[0..3])),
+    Street::Turn => format!("*** TURN *** [{}] [{}]", cards(&board[0..3]), board[3]),
+    Street::River => format!("*** RIVER *** [{}] [{}]", cards(&board[0..4]), board[4]),
+  }
+}
+
+impl fmt::Display for HandHistory {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    writeln!(
+      f,
+      "PokerStars Hand #{}: Hold'em No Limit ({}/{}) - {} UTC",
+      self.id,
+      self.small_blind,
+      self.big_blind,
+      format_time(self.timestamp)
+    )?;
+    writeln!(
+      f,
+      "Table '{}' {}-max Seat #{} is the button",
+      self.table, self.max_seats, self.seats[self.button].number
+    )?;
+    for seat in &self.seats {
+      writeln!(
+        f,
+        "Seat {}: {} ({} in chips)",
+        seat.number, seat.name, seat.stack
+      )?;
+    }
+    for post in &self.forced_bets {
+      let what = match post.post {
+        Post::SmallBlind => "small blind",
+        Post::BigBlind => "big blind",
+        Post::Ante => "the ante",
+      };
+      writeln!(
+        f,
+        "{}: posts {} {}",
+        self.seats[post.seat].name, what, post.amount
+      )?;
+    }
+    writeln!(f, "{}", street_header(Street::Preflop, &self.board))?;
+    for seat in &self.seats {
+      if let Some(hole) = seat.hole {
+        writeln!(f, "Dealt to {} [{}]", seat.name, cards(&hole))?;
+      }
+    }
+
+    let mut ledger = Ledger::new(self);
+    let print_uncalled = |f: &mut fmt::Formatter<'_>, ledger: &Ledger| match ledger.uncalled {
+      Some((seat, amount)) => writeln!(
+        f,
+        "Uncalled bet ({}) returned to {}",
+        amount, self.seats[seat].name
+      ),
+      None => Ok(()),
+    };
+    for play in &self.actions {
+      for street in ledger.advance(play.street) {
+        print_uncalled(f, &ledger)?;
+        writeln!(f, "{}", street_header(street, &self.board))?;
+      }
+      let name = &self.seats[play.seat].name;
+      let before = ledger.current_bet();
+      ledger.apply(play);
+      let all_in = match play.action {
+        Action::Fold | Action::Check => "",
+        _ if ledger.stacks[play.seat] == 0 => " and is all-in",
+        _ => "",
+      };
+      match play.action {
+        Action::Raise(to) => writeln!(f, "{}: raises {} to {}{}", name, to - before, to, all_in)?,
+        action => writeln!(f, "{}: {}{}", name, action, all_in)?,
+      }
+    }
+    ledger.close_street();
+    print_uncalled(f, &ledger)?;
+    // cards dealt after the betting finished, e.g. when players are all-in
+    while let Some(street) = ledger.street.next() {
+      if street.board_len() > self.board.len() {
+        break;
+      }
+      ledger.street = street;
+      writeln!(f, "{}", street_header(street, &self.board))?;
+    }
+
+    let showdown = self.showdown();
+    if !showdown.is_empty() {
+      writeln!(f, "*** SHOW DOWN ***")?;
+      for &seat in &showdown {
+        match (self.seats[seat].hole, self.rank(seat)) {
+          (Some(hole), Some(rank)) => writeln!(
+            f,
+            "{}: shows [{}] ({})",
+            self.seats[seat].name,
+            cards(&hole),
+            rank.describe(&Strings::POKERSTARS)
+          )?,
+          _ => writeln!(f, "{}: mucks hand", self.seats[seat].name)?,
+        }
+      }
+    }
+    for collected in &self.collected {
+      writeln!(
+        f,
+        "{} collected {} from pot",
+        self.seats[collected.seat].name, collected.amount
+      )?;
+    }
+
+    writeln!(f, "*** SUMMARY ***")?;
+    let pot: u64 = ledger.total.iter().sum();
+    let paid: u64 = self.collected.iter().map(|c| c.amount).sum();
+    writeln!(f, "Total pot {} | Rake {}", pot, pot.saturating_sub(paid))?;
+    if !self.board.is_empty() {
+      writeln!(f, "Board [{}]", cards(&self.board))?;
+    }
+    let folds = self.folds();
+    for (i, seat) in self.seats.iter().enumerate() {
+      write!(f, "Seat {}: {}", seat.number, seat.name)?;
+      if i == self.button {
+        write!(f, " (button)")?;
+      }
+      for post in self.forced_bets.iter().filter(|p| p.seat == i) {
+        match post.post {
+          Post::SmallBlind => write!(f, " (small blind)")?,
+          Post::BigBlind => write!(f, " (big blind)")?,
+          Post::Ante => (),
+        }
+      }
+      let won = self.collected(i);
+      match (folds[i], showdown.contains(&i)) {
+        (Some(Street::Preflop), _) if ledger.total[i] == 0 => {
+          writeln!(f, " folded before Flop (didn't bet)")?
+        }
+        (Some(Street::Preflop), _) => writeln!(f, " folded before Flop")?,
+        (Some(street), _) => writeln!(f, " folded on the {}", street)?,
+        (None, true) => match (seat.hole, self.rank(i)) {
+          (Some(hole), Some(rank)) if won > 0 => writeln!(
+            f,
+            " showed [{}] and won ({}) with {}",
+            cards(&hole),
+            won,
+            rank.describe(&Strings::POKERSTARS)
+          )?,
+          (Some(hole), Some(rank)) => writeln!(
+            f,
+            " showed [{}] and lost with {}",
+            cards(&hole),
+            rank.describe(&Strings::POKERSTARS)
+          )?,
+          _ => writeln!(f, " mucked")?,
+        },
+        (None, false) => writeln!(f, " collected ({})", won)?,
+      }
+    }
+    Ok(())
+  }
+}
+
+fn amount(s: &str) -> Result<u64, &'static str> {
+  s.trim().parse().map_err(|_| "Invalid amount.")
+}
+
+fn parse_cards(s: &str) -> Result<Vec<Card>, &'static str> {
+  s.split_whitespace().map(|c| c.parse()).collect()
+}
+
+// the contents of each [...] group in a line
+fn bracketed(line: &str) -> Vec<&str> {
+  line
+    .split('[')
+    .skip(1)
+    .filter_map(|part| part.split_once(']'))
+    .map(|(inside, _)| inside)
+    .collect()
+}
+
+// Splits "name: rest" lines, matching the longest seated name.
+fn speaker<'a>(seats: &[Seat], line: &'a str) -> Option<(usize, &'a str)> {
+  seats
+    .iter()
+    .enumerate()
+    .filter(|(_, seat)| {
+      line.starts_with(seat.name.as_str()) && line[seat.name.len()..].starts_with(": ")
+    })
+    .max_by_key(|(_, seat)| seat.name.len())
+    .map(|(i, seat)| (i, &line[seat.name.len() + 2..]))
+}
+
+fn seat_named(seats: &[Seat], name: &str) -> Result<usize, &'static str> {
+  seats
+    .iter()
+    .position(|s| s.name == name)
+    .ok_or("Unknown player.")
+}
+
+fn parse_action(s: &str) -> Result<Action, &'static str> {
+  let s = s.strip_suffix(" and is all-in").unwrap_or(s);
+  let words: Vec<&str> = s.split_whitespace().collect();
+  match words[..] {
+    ["folds"] => Ok(Action::Fold),
+    ["checks"] => Ok(Action::Check),
+    ["calls", amt] => Ok(Action::Call(amount(amt)?)),
+    ["bets", amt] => Ok(Action::Bet(amount(amt)?)),
+    ["raises", _, "to", to] => Ok(Action::Raise(amount(to)?)),
+    _ => Err("Unrecognised action."),
+  }
+}
+
+// Reads the text format written by Display. Summary lines are skipped, since
+// everything in them is recorded earlier in the history.
+impl FromStr for HandHistory {
+  type Err = &'static str;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let mut lines = s.lines().map(str::trim).filter(|l| !l.is_empty());
+    let mut history = HandHistory::default();
+
+    let header = lines.next().ok_or("Empty hand history.")?;
+    let rest = header
+      .strip_prefix("PokerStars Hand #")
+      .ok_or("Missing hand header.")?;
+    let (id, rest) = rest.split_once(':').ok_or("Missing hand header.")?;
+    history.id = id.parse().map_err(|_| "Invalid hand id.")?;
+    let (_, rest) = rest.split_once('(').ok_or("Missing stakes.")?;
+    let (stakes, rest) = rest.split_once(')').ok_or("Missing stakes.")?;
+    let (small_blind, big_blind) = stakes.split_once('/').ok_or("Missing stakes.")?;
+    history.small_blind = amount(small_blind)?;
+    history.big_blind = amount(big_blind)?;
+    let rest = rest.trim().strip_prefix('-').ok_or("Missing time.")?;
+    let time: Vec<&str> = rest.split_whitespace().take(2).collect();
+    history.timestamp = parse_time(&time.join(" "))?;
+
+    let table = lines.next().ok_or("Missing table line.")?;
+    let rest = table.strip_prefix("Table '").ok_or("Missing table line.")?;
+    let (name, rest) = rest.rsplit_once("' ").ok_or("Missing table line.")?;
+    history.table = name.to_string();
+    let (max_seats, rest) = rest
+      .split_once("-max Seat #")
+      .ok_or("Missing table line.")?;
+    history.max_seats = max_seats.parse().map_err(|_| "Invalid table size.")?;
+    let button: u32 = rest
+      .strip_suffix(" is the button")
+      .and_then(|b| b.parse().ok())
+      .ok_or("Invalid button.")?;
+
+    let mut street = None;
+    let mut showdown = false;
+    for line in lines {
+      if line == "*** SUMMARY ***" {
+        break;
+      }
+      if line == "*** HOLE CARDS ***" {
+        street = Some(Street::Preflop);
+        continue;
+      }
+      if line == "*** SHOW DOWN ***" {
+        showdown = true;
+        continue;
+      }
+      if let Some(rest) = line.strip_prefix("*** ") {
+        let next = street
+          .and_then(|s: Street| s.next())
+          .ok_or("Street out of order.")?;
+        if !rest.starts_with(&format!("{} ***", next.to_string().to_uppercase())) {
+          return Err("Street out of order.");
+        }
+        let dealt = parse_cards(bracketed(rest).last().ok_or("Missing board cards.")?)?;
+        history.board.extend(dealt);
+        if history.board.len() != next.board_len() {
+          return Err("Wrong number of board cards.");
+        }
+        street = Some(next);
+        continue;
+      }
+      let Some(street) = street else {
+        if let Some(rest) = line.strip_prefix("Seat ") {
+          let (number, rest) = rest.split_once(": ").ok_or("Invalid seat.")?;
+          let (name, chips) = rest.rsplit_once(" (").ok_or("Invalid seat.")?;
+          history.seats.push(Seat {
+            number: number.parse().map_err(|_| "Invalid seat.")?,
+            name: name.to_string(),
+            stack: amount(chips.strip_suffix(" in chips)").ok_or("Invalid seat.")?)?,
+            hole: None,
+          });
+          continue;
+        }
+        let (seat, rest) = speaker(&history.seats, line).ok_or("Unknown player.")?;
+        let (post, posted) = if let Some(a) = rest.strip_prefix("posts small blind ") {
+          (Post::SmallBlind, a)
+        } else if let Some(a) = rest.strip_prefix("posts big blind ") {
+          (Post::BigBlind, a)
+        } else if let Some(a) = rest.strip_prefix("posts the ante ") {
+          (Post::Ante, a)
+        } else {
+          return Err("Unrecognised line before the hole cards.");
+        };
+        history.forced_bets.push(ForcedBet {
+          seat,
+          post,
+          amount: amount(posted)?,
+        });
+        continue;
+      };
+      if let Some(rest) = line.strip_prefix("Dealt to ") {
+        let (name, _) = rest.split_once(" [").ok_or("Invalid hole cards.")?;
+        let seat = seat_named(&history.seats, name)?;
+        let hole = parse_cards(bracketed(rest).first().ok_or("Invalid hole cards.")?)?;
+        history.seats[seat].hole = Some(hole.try_into().map_err(|_| "Invalid hole cards.")?);
+      } else if line.starts_with("Uncalled bet (") {
+        // worked out again from the actions
+      } else if let Some((name, rest)) = line.rsplit_once(" collected ") {
+        let won = rest
+          .strip_suffix(" from pot")
+          .ok_or("Invalid collection.")?;
+        history.collected.push(Collected {
+          seat: seat_named(&history.seats, name)?,
+          amount: amount(won)?,
+        });
+      } else {
+        let (seat, rest) = speaker(&history.seats, line).ok_or("Unknown player.")?;
+        if rest.starts_with("shows [") {
+          let hole = parse_cards(bracketed(rest).first().ok_or("Invalid hole cards.")?)?;
+          history.seats[seat].hole = Some(hole.try_into().map_err(|_| "Invalid hole cards.")?);
+        } else if rest == "mucks hand" || rest == "doesn't show hand" {
+        } else if showdown {
+          return Err("Action after the showdown.");
+        } else {
+          history.actions.push(Play {
+            street,
+            seat,
+            action: parse_action(rest)?,
+          });
+        }
+      }
+    }
+
+    history.button = history
+      .seats
+      .iter()
+      .position(|s| s.number == button)
+      .ok_or("Button is not at a seat.")?;
+    history.check()?;
+    Ok(history)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn seat(number: u32, name: &str, stack: u64, hole: &str) -> Seat {
+    Seat {
+      number,
+      name: name.to_string(),
+      stack,
+      hole: Some([hole[0..2].parse().unwrap(), hole[3..5].parse().unwrap()]),
+    }
+  }
+
+  fn play(street: Street, seat: usize, action: Action) -> Play {
+    Play {
+      street,
+      seat,
+      action,
+    }
+  }
+
+  fn showdown_hand() -> HandHistory {
+    HandHistory {
+      id: 1001,
+      table: "Ryerson".to_string(),
+      max_seats: 6,
+      timestamp: 1639253700,
+      small_blind: 10,
+      big_blind: 20,
+      button: 0,
+      seats: vec![
+        seat(1, "Saba", 1500, "AS 3S"),
+        seat(2, "Nick", 1500, "2S 4S"),
+        seat(4, "Prof", 800, "7D 7C"),
+      ],
+      forced_bets: vec![
+        ForcedBet {
+          seat: 1,
+          post: Post::SmallBlind,
+          amount: 10,
+        },
+        ForcedBet {
+          seat: 2,
+          post: Post::BigBlind,
+          amount: 20,
+        },
+      ],
+      actions: vec![
+        play(Street::Preflop, 0, Action::Raise(60)),
+        play(Street::Preflop, 1, Action::Call(50)),
+        play(Street::Preflop, 2, Action::Fold),
+        play(Street::Flop, 1, Action::Check),
+        play(Street::Flop, 0, Action::Bet(100)),
+        play(Street::Flop, 1, Action::Call(100)),
+        play(Street::Turn, 1, Action::Check),
+        play(Street::Turn, 0, Action::Check),
+        play(Street::River, 1, Action::Bet(600)),
+        play(Street::River, 0, Action::Raise(1340)),
+        play(Street::River, 1, Action::Call(740)),
+      ],
+      board: ["9S", "TS", "JS", "QS", "KS"]
+        .iter()
+        .map(|c| c.parse().unwrap())
+        .collect(),
+      collected: vec![Collected {
+        seat: 0,
+        amount: 3020,
+      }],
+    }
+  }
+
+  #[test]
+  fn test_format_time() {
+    assert_eq!(format_time(0), "1970/01/01 00:00:00");
+    assert_eq!(format_time(1639253700), "2021/12/11 20:15:00");
+    assert_eq!(format_time(951782400), "2000/02/29 00:00:00");
+  }
+  #[test]
+  fn test_total_pot() {
+    let hand = showdown_hand();
+    assert_eq!(hand.contributions(), vec![1500, 1500, 20]);
+    assert_eq!(hand.total_pot(), 3020);
+  }
+  #[test]
+  fn test_uncalled_bet() {
+    let mut hand = showdown_hand();
+    hand.actions.truncate(2);
+    hand.actions[1] = play(Street::Preflop, 1, Action::Fold);
+    hand.actions.push(play(Street::Preflop, 2, Action::Fold));
+    hand.board.clear();
+    hand.collected[0].amount = 50;
+    assert_eq!(hand.contributions(), vec![20, 10, 20]);
+    assert!(hand.showdown().is_empty());
+    let text = hand.to_string();
+    assert!(text.contains("Uncalled bet (40) returned to Saba\nSaba collected 50 from pot\n"));
+    assert!(text.contains("Seat 1: Saba (button) collected (50)\n"));
+    assert!(text.contains("Seat 2: Nick (small blind) folded before Flop\n"));
+  }
+  #[test]
+  fn test_parse_time() {
+    assert_eq!(parse_time("2021/12/11 20:15:00"), Ok(1639253700));
+    assert_eq!(parse_time("2000/02/29 00:00:00"), Ok(951782400));
+    assert!(parse_time("2021/13/11 20:15:00").is_err());
+    assert!(parse_time("yesterday").is_err());
+  }
+  #[test]
+  fn test_round_trip() {
+    let hand = showdown_hand();
+    let parsed: HandHistory = hand.to_string().parse().unwrap();
+    assert_eq!(parsed, hand);
+    let mut folded = showdown_hand();
+    folded.actions.truncate(3);
+    folded.actions.push(play(Street::Flop, 1, Action::Check));
+    folded.actions.push(play(Street::Flop, 0, Action::Bet(100)));
+    folded.actions.push(play(Street::Flop, 1, Action::Fold));
+    folded.board.truncate(3);
+    folded.collected[0].amount = 140;
+    assert_eq!(folded.to_string().parse::<HandHistory>().unwrap(), folded);
+  }
+  #[test]
+  fn test_check() {
+    assert_eq!(showdown_hand().check(), Ok(()));
+    assert_eq!(showdown_hand().to_text(), Ok(showdown_hand().to_string()));
+    let mut hand = showdown_hand();
+    hand.button = 3;
+    assert!(hand.check().is_err());
+    assert!(hand.to_text().is_err());
+    let mut hand = showdown_hand();
+    hand.board.truncate(3);
+    assert_eq!(
+      hand.check(),
+      Err("Action on a street the board hasn't reached.")
+    );
+    assert!(hand.to_text().is_err());
+    let mut hand = showdown_hand();
+    hand.actions[0].action = Action::Raise(5);
+    assert_eq!(hand.check(), Err("Raise is below the current bet."));
+    assert!(hand.to_text().is_err());
+    let mut hand = showdown_hand();
+    hand.collected[0].seat = 9;
+    assert!(hand.to_text().is_err());
+  }
+  #[test]
+  fn test_parse_errors() {
+    assert!("".parse::<HandHistory>().is_err());
+    let text = showdown_hand().to_string();
+    assert!(text
+      .replace("raises 740 to 1340", "raises")
+      .parse::<HandHistory>()
+      .is_err());
+    assert!(text
+      .replace("[9S TS JS]", "[9S TS]")
+      .parse::<HandHistory>()
+      .is_err());
+    assert!(text
+      .replace("Dealt to Saba [AS 3S]", "Dealt to Saba [AX 3S]")
+      .parse::<HandHistory>()
+      .is_err());
+  }
+  #[test]
+  fn test_replay() {
+    assert_eq!(showdown_hand().replay(), Ok(vec![3020, 0, 0]));
+    let mut out_of_turn = showdown_hand();
+    out_of_turn.actions.swap(3, 4);
+    assert_eq!(out_of_turn.replay(), Err("Player acted out of turn."));
+    let mut check_to_bet = showdown_hand();
+    check_to_bet.actions[10].action = Action::Check;
+    assert_eq!(check_to_bet.replay(), Err("Cannot check facing a bet."));
+    let mut wrong_winner = showdown_hand();
+    wrong_winner.collected[0].seat = 1;
+    assert_eq!(
+      wrong_winner.replay(),
+      Err("Recorded winnings do not match the best hands.")
+    );
+    let mut short = showdown_hand();
+    short.actions.pop();
+    assert_eq!(
+      short.replay(),
+      Err("Hand ends before the betting is complete.")
+    );
+  }
+  #[test]
+  fn test_to_string() {
+    let hand = showdown_hand();
+    assert_eq!(
+      hand.to_string(),
+      "PokerStars Hand #1001: Hold'em No Limit (10/20) - 2021/12/11 20:15:00 UTC
+Table 'Ryerson' 6-max Seat #1 is the button
+Seat 1: Saba (1500 in chips)
+Seat 2: Nick (1500 in chips)
+Seat 4: Prof (800 in chips)
+Nick: posts small blind 10
+Prof: posts big blind 20
+*** HOLE CARDS ***
+Dealt to Saba [AS 3S]
+Dealt to Nick [2S 4S]
+Dealt to Prof [7D 7C]
+Saba: raises 40 to 60
+Nick: calls 50
+Prof: folds
+*** FLOP *** [9S TS JS]
+Nick: checks
+Saba: bets 100
+Nick: calls 100
+*** TURN *** [9S TS JS] [QS]
+Nick: checks
+Saba: checks
+*** RIVER *** [9S TS JS QS] [KS]
+Nick: bets 600
+Saba: raises 740 to 1340 and is all-in
+Nick: calls 740 and is all-in
+*** SHOW DOWN ***
+Saba: shows [AS 3S] (a Royal Flush)
+Nick: shows [2S 4S] (a straight flush, Nine to King)
+Saba collected 3020 from pot
+*** SUMMARY ***
+Total pot 3020 | Rake 0
+Board [9S TS JS QS KS]
+Seat 1: Saba (button) showed [AS 3S] and won (3020) with a Royal Flush
+Seat 2: Nick (small blind) showed [2S 4S] and lost with a straight flush, Nine to King
+Seat 4: Prof (big blind) folded before Flop
+"
+    );
+  }
+}
@@ -0,0 +1,165 @@
+// Independent Chip Model (Malmuth-Harville) equities for a final table.
+//
+// The chance of a player finishing first is their share of the chips in play.
+// Given the players that finished above, the chance of finishing next is the
+// player's share of the chips that remain. Summing every finishing order
+// exactly is exponential, so tables are capped at MAX_PLAYERS.
+
+pub const MAX_PLAYERS: usize = 20;
+
+#[derive(Clone, Debug, Copy, Eq, PartialEq)]
+pub enum Chop {
+  // Everyone is guaranteed the lowest remaining payout, the rest is split by
+  // chip count.
+  Chip,
+  // Everyone is paid their ICM equity.
+  Icm,
+}
+
+fn validate(stacks: &[f64], payouts: &[f64]) -> Result<(), &'static str> {
+  if stacks.is_empty() {
+    return Err("No players.");
+  }
+  if stacks.len() > MAX_PLAYERS {
+    return Err("Too many players for an exact ICM calculation.");
+  }
+  if stacks.iter().any(|s| !s.is_finite() || *s <= 0.0) {
+    return Err("Stacks must be positive.");
+  }
+  if payouts.iter().any(|p| !p.is_finite() || *p < 0.0) {
+    return Err("Payouts must not be negative.");
+  }
+  Ok(())
+}
+
+// finishes[player][place] is the probability of the player finishing in that
+// place, for every place that pays.
+pub fn finish_probabilities(stacks: &[f64], places: usize) -> Result<Vec<Vec<f64>>, &'static str> {
+  validate(stacks, &[])?;
+  let n = stacks.len();
+  let places = places.min(n);
+  let total: f64 = stacks.iter().sum();
+  let mut finishes = vec![vec![0.0; places]; n];
+  // prob[mask] is the probability that the players in mask took the top
+  // mask.count_ones() places in some order.
+  let mut prob = vec![0.0; 1 << n];
+  let mut placed = vec![0.0; 1 << n];
+  prob[0] = 1.0;
+  for mask in 0..(1usize << n) {
+    if mask != 0 {
+      let low = mask.trailing_zeros() as usize;
+      placed[mask] = placed[mask & (mask - 1)] + stacks[low];
+    }
+    let place = mask.count_ones() as usize;
+    if place >= places || prob[mask] == 0.0 {
+      continue;
+    }
+    let remaining = total - placed[mask];
+    for (player, stack) in stacks.iter().enumerate() {
+      if mask & (1 << player) != 0 {
+        continue;
+      }
+      let p = prob[mask] * stack / remaining;
+      finishes[player][place] += p;
+      prob[mask | (1 << player)] += p;
+    }
+  }
+  Ok(finishes)
+}
+
+// Expected prize money for each player. payouts[0] is first place; places
+// beyond the number of players are ignored.
+pub fn equities(stacks: &[f64], payouts: &[f64]) -> Result<Vec<f64>, &'static str> {
+  validate(stacks, payouts)?;
+  let finishes = finish_probabilities(stacks, payouts.len())?;
+  Ok(
+    finishes
+      .iter()
+      .map(|places| places.iter().zip(payouts).map(|(p, prize)| p * prize).sum())
+      .collect(),
+  )
+}
+
+pub fn chip_chop(stacks: &[f64], payouts: &[f64]) -> Result<Vec<f64>, &'static str> {
+  validate(stacks, payouts)?;
+  let n = stacks.len();
+  let pool: f64 = payouts.iter().take(n).sum();
+  let floor = if payouts.len() >= n {
+    payouts[n - 1]
+  } else {
+    0.0
+  };
+  let total: f64 = stacks.iter().sum();
+  let rest = pool - floor * n as f64;
+  Ok(stacks.iter().map(|s| floor + rest * s / total).collect())
+}
+
+pub fn chop(stacks: &[f64], payouts: &[f64], method: Chop) -> Result<Vec<f64>, &'static str> {
+  match method {
+    Chop::Chip => chip_chop(stacks, payouts),
+    Chop::Icm => equities(stacks, payouts),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn assert_close(left: &[f64], right: &[f64]) {
+    assert_eq!(left.len(), right.len());
+    for (l, r) in left.iter().zip(right) {
+      assert!((l - r).abs() < 1e-9, "{:?} != {:?}", left, right);
+    }
+  }
+
+  #[test]
+  fn test_heads_up() {
+    let equity = equities(&[3000.0, 1000.0], &[100.0, 60.0]).unwrap();
+    assert_close(&equity, &[90.0, 70.0]);
+  }
+  #[test]
+  fn test_three_handed() {
+    let equity = equities(&[50.0, 30.0, 20.0], &[50.0, 30.0, 20.0]).unwrap();
+    // P(second place) for the 50 stack: 0.3 * 50/70 + 0.2 * 50/80
+    let second = 0.3 * 50.0 / 70.0 + 0.2 * 50.0 / 80.0;
+    let third = 1.0 - 0.5 - second;
+    assert!((equity[0] - (0.5 * 50.0 + second * 30.0 + third * 20.0)).abs() < 1e-9);
+    assert!((equity.iter().sum::<f64>() - 100.0).abs() < 1e-9);
+    assert!(equity[0] > equity[1] && equity[1] > equity[2]);
+  }
+  #[test]
+  fn test_equal_stacks() {
+    let equity = equities(&[10.0; 6], &[50.0, 30.0, 20.0]).unwrap();
+    assert_close(&equity, &[100.0 / 6.0; 6]);
+  }
+  #[test]
+  fn test_finish_probabilities() {
+    let finishes = finish_probabilities(&[40.0, 35.0, 25.0, 10.0, 5.0], 5).unwrap();
+    for player in &finishes {
+      assert!((player.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+    }
+    for place in 0..5 {
+      assert!((finishes.iter().map(|p| p[place]).sum::<f64>() - 1.0).abs() < 1e-9);
+    }
+  }
+  #[test]
+  fn test_chop() {
+    let stacks = [600.0, 300.0, 100.0];
+    let payouts = [500.0, 300.0, 200.0];
+    assert_close(
+      &chop(&stacks, &payouts, Chop::Chip).unwrap(),
+      &[440.0, 320.0, 240.0],
+    );
+    let icm = chop(&stacks, &payouts, Chop::Icm).unwrap();
+    assert!((icm.iter().sum::<f64>() - 1000.0).abs() < 1e-9);
+    // ICM favours the short stack relative to a chip chop.
+    assert!(icm[2] > 240.0 && icm[0] < 440.0);
+  }
+  #[test]
+  fn test_invalid() {
+    assert!(equities(&[], &[100.0]).is_err());
+    assert!(equities(&[100.0, 0.0], &[100.0]).is_err());
+    assert!(equities(&[100.0, 50.0], &[-1.0]).is_err());
+    assert!(equities(&[1.0; MAX_PLAYERS + 1], &[100.0]).is_err());
+  }
+}
@@ -0,0 +1,424 @@
+// Hands that only differ by a relabelling of suits play the same, so tables
+// can be keyed by their suit isomorphism class instead of by the hand.
+//
+// A hand is dealt in rounds (the hole cards, then the flop, turn and river).
+// Within a round order doesn't matter, but which round a card came in does.
+// The indexer follows Waugh's construction: each suit holds a set of faces
+// per round, and a hand up to isomorphism is the multiset of those four
+// per-suit configurations, which can be ranked exactly.
+
+use crate::Card;
+use crate::Face;
+use crate::Street;
+use crate::Suit;
+
+// every relabelling of the four suits
+fn permutations() -> Vec<[usize; 4]> {
+  let mut perms = Vec::with_capacity(24);
+  for a in 0..4 {
+    for b in (0..4).filter(|&b| b != a) {
+      for c in (0..4).filter(|&c| c != a && c != b) {
+        perms.push([a, b, c, 6 - a - b - c]);
+      }
+    }
+  }
+  perms
+}
+
+fn relabel(card: Card, perm: &[usize; 4]) -> Card {
+  Card::new(card.face(), Suit::all()[perm[card.suit() as usize]])
+}
+
+// The representative of a hand's isomorphism class: of all suit relabellings,
+// the one whose rounds, each sorted high to low, compare greatest.
+pub fn canonicalize(rounds: &[&[Card]]) -> Vec<Vec<Card>> {
+  permutations()
+    .iter()
+    .map(|perm| {
+      rounds
+        .iter()
+        .map(|round| {
+          let mut cards: Vec<Card> = round.iter().map(|&c| relabel(c, perm)).collect();
+          cards.sort_by(|a, b| b.face().cmp(&a.face()).then(b.suit().cmp(&a.suit())));
+          cards
+        })
+        .collect::<Vec<Vec<Card>>>()
+    })
+    .max_by(|a, b| {
+      let key = |r: &Vec<Vec<Card>>| -> Vec<(Face, Suit)> {
+        r.iter().flatten().map(|c| (c.face(), c.suit())).collect()
+      };
+      key(a).cmp(&key(b))
+    })
+    .unwrap()
+}
+
+fn choose(n: u64, k: u64) -> u64 {
+  if k > n {
+    return 0;
+  }
+  let mut result: u128 = 1;
+  for i in 0..k {
+    result = result * (n - i) as u128 / (i + 1) as u128;
+  }
+  result as u64
+}
+
+// colex rank of a strictly increasing sequence
+fn rank_set(items: &[u64]) -> u64 {
+  items
+    .iter()
+    .enumerate()
+    .map(|(i, &x)| choose(x, i as u64 + 1))
+    .sum()
+}
+
+fn unrank_set(mut index: u64, k: usize) -> Vec<u64> {
+  let mut items = vec![0; k];
+  for i in (0..k).rev() {
+    let k = i as u64 + 1;
+    // the largest x with choose(x, k) <= index, by doubling then bisecting
+    let mut high = k;
+    while choose(high, k) <= index {
+      high *= 2;
+    }
+    let mut low = i as u64;
+    while high - low > 1 {
+      let mid = (low + high) / 2;
+      if choose(mid, k) <= index {
+        low = mid;
+      } else {
+        high = mid;
+      }
+    }
+    items[i] = low;
+    index -= choose(low, k);
+  }
+  items
+}
+
+// cards per round in one suit
+type Shape = Vec<u8>;
+
+fn shape_size(shape: &Shape) -> u64 {
+  let mut left = 13;
+  let mut size = 1;
+  for &k in shape {
+    size *= choose(left, k as u64);
+    left -= k as u64;
+  }
+  size
+}
+
+// Ranks a suit's faces round by round, each round among the faces still
+// unused, least significant round first.
+fn rank_suit(masks: &[u16]) -> u64 {
+  let mut used = 0u16;
+  let mut index = 0;
+  let mut scale = 1;
+  for &mask in masks {
+    let free: Vec<usize> = (0..13).filter(|f| used & (1 << f) == 0).collect();
+    let positions: Vec<u64> = free
+      .iter()
+      .enumerate()
+      .filter(|(_, &f)| mask & (1 << f) != 0)
+      .map(|(i, _)| i as u64)
+      .collect();
+    index += scale * rank_set(&positions);
+    scale *= choose(free.len() as u64, positions.len() as u64);
+    used |= mask;
+  }
+  index
+}
+
+fn unrank_suit(mut index: u64, shape: &Shape) -> Vec<u16> {
+  let mut used = 0u16;
+  let mut masks = Vec::with_capacity(shape.len());
+  for &k in shape {
+    let free: Vec<usize> = (0..13).filter(|f| used & (1 << f) == 0).collect();
+    let size = choose(free.len() as u64, k as u64);
+    let positions = unrank_set(index % size, k as usize);
+    index /= size;
+    let mask = positions
+      .iter()
+      .fold(0u16, |m, &p| m | 1 << free[p as usize]);
+    masks.push(mask);
+    used |= mask;
+  }
+  masks
+}
+
+// Four suit shapes, largest first, and where their hands start.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Configuration {
+  shapes: [Shape; 4],
+  offset: u64,
+  size: u64,
+}
+
+// runs of equal shapes as (start, length)
+fn groups(shapes: &[Shape; 4]) -> Vec<(usize, usize)> {
+  let mut groups: Vec<(usize, usize)> = Vec::new();
+  for i in 0..4 {
+    match groups.last_mut() {
+      Some((start, len)) if shapes[*start] == shapes[i] => *len += 1,
+      _ => groups.push((i, 1)),
+    }
+  }
+  groups
+}
+
+fn configuration_size(shapes: &[Shape; 4]) -> u64 {
+  groups(shapes)
+    .iter()
+    .map(|&(start, len)| {
+      let n = shape_size(&shapes[start]);
+      choose(n + len as u64 - 1, len as u64)
+    })
+    .product()
+}
+
+fn distribute(rounds: &[usize], round: usize, shapes: &mut [Shape; 4], out: &mut Vec<[Shape; 4]>) {
+  if round == rounds.len() {
+    let mut sorted = shapes.clone();
+    sorted.sort_by(|a, b| b.cmp(a));
+    out.push(sorted);
+    return;
+  }
+  // every way to split this round's cards over the four suits
+  let n = rounds[round];
+  for a in 0..=n {
+    for b in 0..=n - a {
+      for c in 0..=n - a - b {
+        let split = [a, b, c, n - a - b - c];
+        let fits =
+          (0..4).all(|s| shapes[s].iter().map(|&k| k as usize).sum::<usize>() + split[s] <= 13);
+        if fits {
+          for s in 0..4 {
+            shapes[s].push(split[s] as u8);
+          }
+          distribute(rounds, round + 1, shapes, out);
+          for shape in shapes.iter_mut() {
+            shape.pop();
+          }
+        }
+      }
+    }
+  }
+}
+
+// A perfect index of hands up to suit isomorphism, from 0 to size() - 1.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HandIndexer {
+  rounds: Vec<usize>,
+  configurations: Vec<Configuration>,
+}
+
+impl HandIndexer {
+  // cards dealt in each round, e.g. [2, 3] for hole cards and a flop
+  pub fn new(rounds: &[usize]) -> Self {
+    assert!(rounds.iter().sum::<usize>() <= 52, "Too many cards.");
+    let mut all = Vec::new();
+    distribute(rounds, 0, &mut Default::default(), &mut all);
+    all.sort();
+    all.dedup();
+    let mut offset = 0;
+    let configurations = all
+      .into_iter()
+      .map(|shapes| {
+        let size = configuration_size(&shapes);
+        let configuration = Configuration {
+          shapes,
+          offset,
+          size,
+        };
+        offset += size;
+        configuration
+      })
+      .collect();
+    Self {
+      rounds: rounds.to_vec(),
+      configurations,
+    }
+  }
+  // Hole cards and the board up to a street, the turn and river each their
+  // own round. new(&[2, 5]) treats the whole board as one round instead.
+  pub fn street(street: Street) -> Self {
+    let rounds = [2, 3, 1, 1];
+    Self::new(&rounds[..street as usize + 1])
+  }
+  pub fn rounds(&self) -> &[usize] {
+    &self.rounds
+  }
+  pub fn size(&self) -> u64 {
+    self.configurations.last().map_or(0, |c| c.offset + c.size)
+  }
+  // cards in round order, e.g. the hole cards then the flop
+  pub fn index(&self, cards: &[Card]) -> Result<u64, &'static str> {
+    if cards.len() != self.rounds.iter().sum::<usize>() {
+      return Err("Wrong number of cards for the rounds.");
+    }
+    let mut masks = vec![[0u16; 4]; self.rounds.len()];
+    let mut start = 0;
+    for (round, &n) in self.rounds.iter().enumerate() {
+      for card in &cards[start..start + n] {
+        let bit = 1 << card.face() as usize;
+        let suit = card.suit() as usize;
+        if (0..=round).any(|r| masks[r][suit] & bit != 0) {
+          return Err("Repeated card.");
+        }
+        masks[round][suit] |= bit;
+      }
+      start += n;
+    }
+    // each suit's shape and its index among hands of that shape
+    let mut suits: Vec<(Shape, u64)> = (0..4)
+      .map(|s| {
+        let suit_masks: Vec<u16> = (0..self.rounds.len()).map(|r| masks[r][s]).collect();
+        let shape = suit_masks.iter().map(|m| m.count_ones() as u8).collect();
+        (shape, rank_suit(&suit_masks))
+      })
+      .collect();
+    suits.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+    let shapes: [Shape; 4] = suits
+      .iter()
+      .map(|(shape, _)| shape.clone())
+      .collect::<Vec<Shape>>()
+      .try_into()
+      .unwrap();
+    let position = self
+      .configurations
+      .binary_search_by(|c| c.shapes.cmp(&shapes))
+      .unwrap();
+    let configuration = &self.configurations[position];
+    let mut index = 0;
+    let mut scale = 1;
+    for (start, len) in groups(&shapes) {
+      let n = shape_size(&shapes[start]);
+      let multiset: Vec<u64> = (0..len).map(|i| suits[start + i].1 + i as u64).collect();
+      index += scale * rank_set(&multiset);
+      scale *= choose(n + len as u64 - 1, len as u64);
+    }
+    Ok(configuration.offset + index)
+  }
+  // The canonical hand for an index, in round order with each round sorted.
+  pub fn unindex(&self, index: u64) -> Option<Vec<Card>> {
+    if index >= self.size() {
+      return None;
+    }
+    let position = self
+      .configurations
+      .partition_point(|c| c.offset + c.size <= index);
+    let configuration = &self.configurations[position];
+    let mut rest = index - configuration.offset;
+    let mut rounds: Vec<Vec<Card>> = vec![Vec::new(); self.rounds.len()];
+    for (start, len) in groups(&configuration.shapes) {
+      let shape = &configuration.shapes[start];
+      let n = shape_size(shape);
+      let size = choose(n + len as u64 - 1, len as u64);
+      let multiset = unrank_set(rest % size, len);
+      rest /= size;
+      for (i, &item) in multiset.iter().enumerate() {
+        let suit = Suit::all()[start + i];
+        for (round, mask) in unrank_suit(item - i as u64, shape).iter().enumerate() {
+          for face in (0..13).filter(|f| mask & (1 << f) != 0) {
+            rounds[round].push(Card::new(Face::all()[face], suit));
+          }
+        }
+      }
+    }
+    for round in rounds.iter_mut() {
+      round.sort_by(|a, b| b.face().cmp(&a.face()).then(a.suit().cmp(&b.suit())));
+    }
+    Some(rounds.concat())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::Rng;
+
+  fn cards(s: &str) -> Vec<Card> {
+    s.split_whitespace().map(|c| c.parse().unwrap()).collect()
+  }
+
+  #[test]
+  fn test_sizes() {
+    assert_eq!(HandIndexer::street(Street::Preflop).size(), 169);
+    assert_eq!(HandIndexer::street(Street::Flop).size(), 1_286_792);
+    assert_eq!(HandIndexer::street(Street::Turn).size(), 55_190_538);
+    assert_eq!(HandIndexer::street(Street::River).size(), 2_428_287_420);
+    // with the board as one round instead
+    assert_eq!(HandIndexer::new(&[2, 4]).size(), 13_960_050);
+    assert_eq!(HandIndexer::new(&[2, 5]).size(), 123_156_254);
+  }
+  #[test]
+  fn test_preflop() {
+    let indexer = HandIndexer::street(Street::Preflop);
+    let mut seen = [false; 169];
+    for a in Card::all() {
+      for b in Card::all().into_iter().filter(|&b| b != a) {
+        let index = indexer.index(&[a, b]).unwrap();
+        seen[index as usize] = true;
+        let canonical = indexer.unindex(index).unwrap();
+        assert_eq!(canonical[0].face(), a.face().max(b.face()));
+        assert_eq!(
+          canonical[0].suit() == canonical[1].suit(),
+          a.suit() == b.suit()
+        );
+      }
+    }
+    assert!(seen.iter().all(|&s| s));
+  }
+  #[test]
+  fn test_isomorphic_hands() {
+    let indexer = HandIndexer::street(Street::Flop);
+    let a = indexer.index(&cards("AH KH 2H 7C 9S")).unwrap();
+    let b = indexer.index(&cards("KD AD 7S 2D 9C")).unwrap();
+    assert_eq!(a, b);
+    // the suited card moves from the hole to the board
+    let c = indexer.index(&cards("AH KC 2H 7C 9S")).unwrap();
+    assert_ne!(a, c);
+    // rounds matter, order within them doesn't
+    let d = indexer.index(&cards("AH 2H KH 7C 9S")).unwrap();
+    assert_ne!(a, d);
+    assert!(indexer.index(&cards("AH AH 2H 7C 9S")).is_err());
+    assert!(indexer.index(&cards("AH KH 2H 7C")).is_err());
+  }
+  #[test]
+  fn test_round_trip() {
+    let mut rng = Rng::new(38);
+    let mut deck = Card::all();
+    for street in [Street::Flop, Street::Turn, Street::River] {
+      let indexer = HandIndexer::street(street);
+      let n = indexer.rounds().iter().sum();
+      for _ in 0..300 {
+        rng.partial_shuffle(&mut deck, n);
+        let index = indexer.index(&deck[..n]).unwrap();
+        let canonical = indexer.unindex(index).unwrap();
+        assert_eq!(indexer.index(&canonical), Ok(index));
+        // relabelling suits keeps the index
+        let mut perm = [0, 1, 2, 3];
+        rng.shuffle(&mut perm);
+        let relabelled: Vec<Card> = deck[..n].iter().map(|&c| relabel(c, &perm)).collect();
+        assert_eq!(indexer.index(&relabelled), Ok(index));
+      }
+      let last = indexer.size() - 1;
+      assert_eq!(indexer.index(&indexer.unindex(last).unwrap()), Ok(last));
+      assert_eq!(indexer.unindex(last + 1), None);
+    }
+  }
+  #[test]
+  fn test_canonicalize() {
+    let a = canonicalize(&[&cards("AH KH"), &cards("2H 7C 9S")]);
+    let b = canonicalize(&[&cards("KD AD"), &cards("7S 9C 2D")]);
+    assert_eq!(a, b);
+    assert_eq!(a[0], cards("AS KS"));
+    let indexer = HandIndexer::street(Street::Flop);
+    assert_eq!(
+      indexer.index(&a.concat()),
+      indexer.index(&cards("AH KH 2H 7C 9S"))
+    );
+  }
+}
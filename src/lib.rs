@@ -1,15 +1,52 @@
+mod action;
+pub use action::Action;
 mod card;
 pub use card::Card;
+mod combo;
+pub use combo::Combo;
 mod deal;
 pub use deal::Deal;
 mod deck;
-pub use deck::Deck;
+pub use deck::{Deck, Order, Permutation};
 mod face;
 pub use face::Face;
+mod grid;
+pub use grid::Grid;
 mod hand;
 pub use hand::Hand;
+mod range;
+pub use range::{HandClass, Range};
 mod rank;
 pub use rank::Rank;
+mod rng;
+pub use rng::Rng;
+#[cfg(feature = "serde")]
+mod serialize;
+mod street;
+pub use street::Street;
+mod strings;
+pub use strings::Strings;
 mod suit;
 pub use suit::Suit;
+mod table;
+pub use table::{Legal, Table};
+pub mod agent;
+pub mod arena;
+pub mod cfr;
+pub mod cli;
+pub mod codec;
+pub mod dealing;
+pub mod equity;
+pub mod eval;
+pub mod game;
+pub mod grade;
+pub mod history;
+pub mod icm;
+pub mod iso;
+#[cfg(feature = "ohh")]
+pub mod ohh;
+pub mod outs;
 pub mod poker;
+pub mod preflop;
+pub mod pushfold;
+pub mod texture;
@@ -0,0 +1,719 @@
+// Reading and writing hand histories in the Open Hand History JSON format
+// (https://hh-specs.handhistory.org), behind the `ohh` feature.
+//
+// Players get ids from their order in HandHistory::seats. Hole cards are
+// written as "Dealt Cards" actions before the preflop betting and again as
+// "Shows Cards" for the players at showdown, and cards are written the way
+// the spec does, "Ah" and "Td". Raise amounts are the total raised to, and
+// chips are whole numbers. A file may hold several hands separated by
+// whitespace.
+
+use crate::history::format_time;
+use crate::history::parse_time;
+use crate::history::{Collected, ForcedBet, HandHistory, Play, Post, Seat};
+use crate::Action;
+use crate::Card;
+use crate::Street;
+use serde::{Deserialize, Serialize};
+
+pub const SPEC_VERSION: &str = "1.4.6";
+
+#[derive(Serialize, Deserialize)]
+struct Document {
+  ohh: Ohh,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Ohh {
+  spec_version: String,
+  #[serde(default)]
+  site_name: String,
+  #[serde(default)]
+  network_name: String,
+  #[serde(default)]
+  internal_version: String,
+  #[serde(default)]
+  tournament: bool,
+  game_number: String,
+  start_date_utc: String,
+  #[serde(default)]
+  table_name: String,
+  #[serde(default)]
+  table_size: u32,
+  game_type: String,
+  bet_limit: BetLimit,
+  dealer_seat: u32,
+  #[serde(with = "chips")]
+  small_blind_amount: u64,
+  #[serde(with = "chips")]
+  big_blind_amount: u64,
+  #[serde(default, with = "chips")]
+  ante_amount: u64,
+  players: Vec<Player>,
+  rounds: Vec<Round>,
+  #[serde(default)]
+  pots: Vec<Pot>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BetLimit {
+  bet_type: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Player {
+  id: u64,
+  seat: u32,
+  name: String,
+  #[serde(with = "chips")]
+  starting_stack: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Round {
+  id: u64,
+  street: String,
+  #[serde(default)]
+  cards: Vec<String>,
+  actions: Vec<RoundAction>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RoundAction {
+  action_number: u64,
+  player_id: u64,
+  action: String,
+  #[serde(default, with = "chips")]
+  amount: u64,
+  #[serde(default)]
+  is_allin: bool,
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  cards: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Pot {
+  number: u64,
+  #[serde(with = "chips")]
+  amount: u64,
+  #[serde(default, with = "chips")]
+  rake: u64,
+  player_wins: Vec<PlayerWin>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PlayerWin {
+  player_id: u64,
+  #[serde(with = "chips")]
+  win_amount: u64,
+}
+
+// Amounts are numbers in the spec, and may have decimals; chips here don't.
+mod chips {
+  use serde::{de, Deserialize, Deserializer, Serializer};
+
+  pub fn serialize<S: Serializer>(amount: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_u64(*amount)
+  }
+  pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    let amount = f64::deserialize(deserializer)?;
+    if amount < 0.0 || amount.fract() != 0.0 || amount > u64::MAX as f64 {
+      return Err(de::Error::custom("Amounts must be whole numbers of chips."));
+    }
+    Ok(amount as u64)
+  }
+}
+
+fn card_string(card: &Card) -> String {
+  format!("{}{}", card.face(), card.suit().to_string().to_lowercase())
+}
+
+fn cards_from(cards: &[String]) -> Result<Vec<Card>, &'static str> {
+  cards.iter().map(|c| c.parse()).collect()
+}
+
+fn street_name(street: Street) -> &'static str {
+  match street {
+    Street::Preflop => "Preflop",
+    Street::Flop => "Flop",
+    Street::Turn => "Turn",
+    Street::River => "River",
+  }
+}
+
+// What each player has left, to mark actions that put them all in.
+struct Stacks {
+  stacks: Vec<u64>,
+  street_in: Vec<u64>,
+}
+
+impl Stacks {
+  fn put(&mut self, seat: usize, action: Action) -> bool {
+    let added = match action {
+      Action::Fold | Action::Check => return false,
+      Action::Call(amount) | Action::Bet(amount) => amount,
+      Action::Raise(to) => to.saturating_sub(self.street_in[seat]),
+    };
+    let added = added.min(self.stacks[seat]);
+    self.stacks[seat] -= added;
+    self.street_in[seat] += added;
+    self.stacks[seat] == 0
+  }
+}
+
+// The pot each player was in on, main pot first, as (amount, eligible).
+fn pots(history: &HandHistory) -> Vec<(u64, Vec<usize>)> {
+  let contributions = history.contributions();
+  let folds = history.folds();
+  let live: Vec<usize> = (0..history.seats.len())
+    .filter(|&s| folds[s].is_none())
+    .collect();
+  let mut levels: Vec<u64> = live.iter().map(|&s| contributions[s]).collect();
+  levels.sort();
+  levels.dedup();
+  let mut pots = Vec::new();
+  let mut previous = 0;
+  for (i, &level) in levels.iter().enumerate() {
+    // folded chips above the last level still belong in the last pot
+    let top = if i + 1 == levels.len() {
+      u64::MAX
+    } else {
+      level
+    };
+    let amount = contributions
+      .iter()
+      .map(|&c| c.min(top) - c.min(previous))
+      .sum();
+    let eligible = live
+      .iter()
+      .copied()
+      .filter(|&s| contributions[s] >= level)
+      .collect();
+    pots.push((amount, eligible));
+    previous = level;
+  }
+  pots
+}
+
+fn to_ohh(history: &HandHistory) -> Ohh {
+  let id = |seat: usize| seat as u64;
+  let mut rounds = Vec::new();
+  let mut number = 0;
+  let mut next = || {
+    number += 1;
+    number
+  };
+  let mut stacks = Stacks {
+    stacks: history.seats.iter().map(|s| s.stack).collect(),
+    street_in: vec![0; history.seats.len()],
+  };
+
+  let mut preflop = Vec::new();
+  for bet in &history.forced_bets {
+    let action = match bet.post {
+      Post::SmallBlind => "Post SB",
+      Post::BigBlind => "Post BB",
+      Post::Ante => "Post Ante",
+    };
+    let amount = bet.amount.min(stacks.stacks[bet.seat]);
+    stacks.stacks[bet.seat] -= amount;
+    if bet.post != Post::Ante {
+      stacks.street_in[bet.seat] += amount;
+    }
+    preflop.push(RoundAction {
+      action_number: next(),
+      player_id: id(bet.seat),
+      action: action.to_string(),
+      amount: bet.amount,
+      is_allin: stacks.stacks[bet.seat] == 0,
+      cards: Vec::new(),
+    });
+  }
+  for (seat, player) in history.seats.iter().enumerate() {
+    if let Some(hole) = player.hole {
+      preflop.push(RoundAction {
+        action_number: next(),
+        player_id: id(seat),
+        action: "Dealt Cards".to_string(),
+        amount: 0,
+        is_allin: false,
+        cards: hole.iter().map(card_string).collect(),
+      });
+    }
+  }
+
+  let mut actions = vec![preflop, Vec::new(), Vec::new(), Vec::new()];
+  let mut street = Street::Preflop;
+  for play in &history.actions {
+    if play.street != street {
+      street = play.street;
+      stacks.street_in.iter_mut().for_each(|s| *s = 0);
+    }
+    let (action, amount) = match play.action {
+      Action::Fold => ("Fold", 0),
+      Action::Check => ("Check", 0),
+      Action::Call(amount) => ("Call", amount),
+      Action::Bet(amount) => ("Bet", amount),
+      Action::Raise(to) => ("Raise", to),
+    };
+    let is_allin = stacks.put(play.seat, play.action);
+    actions[play.street as usize].push(RoundAction {
+      action_number: next(),
+      player_id: id(play.seat),
+      action: action.to_string(),
+      amount,
+      is_allin,
+      cards: Vec::new(),
+    });
+  }
+  for (i, actions) in actions.into_iter().enumerate() {
+    let street = Street::all()[i];
+    let cards = match street {
+      Street::Preflop => &history.board[..0],
+      _ if history.board.len() < street.board_len() => break,
+      Street::Flop => &history.board[..3],
+      _ => &history.board[street.board_len() - 1..street.board_len()],
+    };
+    rounds.push(Round {
+      id: i as u64,
+      street: street_name(street).to_string(),
+      cards: cards.iter().map(card_string).collect(),
+      actions,
+    });
+  }
+  let shows: Vec<RoundAction> = history
+    .showdown()
+    .into_iter()
+    .filter_map(|seat| {
+      let hole = history.seats[seat].hole?;
+      Some(RoundAction {
+        action_number: next(),
+        player_id: id(seat),
+        action: "Shows Cards".to_string(),
+        amount: 0,
+        is_allin: false,
+        cards: hole.iter().map(card_string).collect(),
+      })
+    })
+    .collect();
+  if !shows.is_empty() {
+    rounds.push(Round {
+      id: rounds.len() as u64,
+      street: "Showdown".to_string(),
+      cards: Vec::new(),
+      actions: shows,
+    });
+  }
+
+  // hand out what each player collected over the pots they were in on
+  let mut owed: Vec<u64> = (0..history.seats.len())
+    .map(|s| history.collected(s))
+    .collect();
+  let pots = pots(history)
+    .into_iter()
+    .enumerate()
+    .map(|(number, (amount, eligible))| {
+      let mut left = amount;
+      let mut player_wins = Vec::new();
+      for seat in eligible {
+        let won = owed[seat].min(left);
+        if won > 0 {
+          owed[seat] -= won;
+          left -= won;
+          player_wins.push(PlayerWin {
+            player_id: id(seat),
+            win_amount: won,
+          });
+        }
+      }
+      Pot {
+        number: number as u64,
+        amount,
+        rake: 0,
+        player_wins,
+      }
+    })
+    .collect();
+
+  let dealer_seat = history.seats.get(history.button).map_or(0, |s| s.number);
+  let blind = |post: Post| {
+    history
+      .forced_bets
+      .iter()
+      .find(|b| b.post == post)
+      .map_or(0, |b| b.amount)
+  };
+  let time = format_time(history.timestamp);
+  Ohh {
+    spec_version: SPEC_VERSION.to_string(),
+    site_name: String::new(),
+    network_name: String::new(),
+    internal_version: env!("CARGO_PKG_VERSION").to_string(),
+    tournament: false,
+    game_number: history.id.to_string(),
+    start_date_utc: format!("{}Z", time.replace('/', "-").replace(' ', "T")),
+    table_name: history.table.clone(),
+    table_size: history.max_seats,
+    game_type: "Holdem".to_string(),
+    bet_limit: BetLimit {
+      bet_type: "NL".to_string(),
+    },
+    dealer_seat,
+    small_blind_amount: history.small_blind,
+    big_blind_amount: history.big_blind,
+    ante_amount: blind(Post::Ante),
+    players: history
+      .seats
+      .iter()
+      .enumerate()
+      .map(|(i, seat)| Player {
+        id: id(i),
+        seat: seat.number,
+        name: seat.name.clone(),
+        starting_stack: seat.stack,
+      })
+      .collect(),
+    rounds,
+    pots,
+  }
+}
+
+fn from_ohh(ohh: Ohh) -> Result<HandHistory, &'static str> {
+  if ohh.game_type != "Holdem" || ohh.bet_limit.bet_type != "NL" {
+    return Err("Only no limit hold'em hands are supported.");
+  }
+  let seat_of = |player: u64| {
+    ohh
+      .players
+      .iter()
+      .position(|p| p.id == player)
+      .ok_or("Action by an unknown player.")
+  };
+  let mut seats: Vec<Seat> = ohh
+    .players
+    .iter()
+    .map(|p| Seat {
+      number: p.seat,
+      name: p.name.clone(),
+      stack: p.starting_stack,
+      hole: None,
+    })
+    .collect();
+  let button = ohh
+    .players
+    .iter()
+    .position(|p| p.seat == ohh.dealer_seat)
+    .ok_or("Nobody is in the dealer seat.")?;
+  let date = ohh.start_date_utc.get(..19).ok_or("Invalid time.")?;
+  let timestamp = parse_time(&date.replace('-', "/").replace('T', " "))?;
+  let mut history = HandHistory {
+    id: ohh
+      .game_number
+      .parse()
+      .map_err(|_| "Game number must be a number.")?,
+    table: ohh.table_name.clone(),
+    max_seats: ohh.table_size,
+    timestamp,
+    small_blind: ohh.small_blind_amount,
+    big_blind: ohh.big_blind_amount,
+    button,
+    ..Default::default()
+  };
+  for round in &ohh.rounds {
+    let street = match round.street.as_str() {
+      "Preflop" => Some(Street::Preflop),
+      "Flop" => Some(Street::Flop),
+      "Turn" => Some(Street::Turn),
+      "River" => Some(Street::River),
+      "Showdown" => None,
+      _ => return Err("Unknown street."),
+    };
+    let dealt = cards_from(&round.cards)?;
+    let expected = match street {
+      Some(Street::Flop) => 3,
+      Some(Street::Turn | Street::River) => 1,
+      _ => 0,
+    };
+    if dealt.len() != expected {
+      return Err("Round has the wrong number of cards.");
+    }
+    history.board.extend(dealt);
+    if street.is_some_and(|s| s.board_len() != history.board.len()) {
+      return Err("Rounds are out of order.");
+    }
+    for action in &round.actions {
+      let seat = seat_of(action.player_id)?;
+      let post = |post: Post| ForcedBet {
+        seat,
+        post,
+        amount: action.amount,
+      };
+      let play = match (action.action.as_str(), street) {
+        ("Post SB", _) => {
+          history.forced_bets.push(post(Post::SmallBlind));
+          continue;
+        }
+        ("Post BB", _) => {
+          history.forced_bets.push(post(Post::BigBlind));
+          continue;
+        }
+        ("Post Ante", _) => {
+          history.forced_bets.push(post(Post::Ante));
+          continue;
+        }
+        ("Dealt Cards" | "Shows Cards" | "Mucks Cards", _) => {
+          if !action.cards.is_empty() {
+            let hole = cards_from(&action.cards)?;
+            seats[seat].hole = Some(
+              hole
+                .try_into()
+                .map_err(|_| "Hole cards must be two cards.")?,
+            );
+          }
+          continue;
+        }
+        (_, None) => return Err("Betting recorded at showdown."),
+        ("Fold", _) => Action::Fold,
+        ("Check", _) => Action::Check,
+        ("Call", _) => Action::Call(action.amount),
+        ("Bet", _) => Action::Bet(action.amount),
+        ("Raise", _) => Action::Raise(action.amount),
+        _ => return Err("Unsupported action."),
+      };
+      history.actions.push(Play {
+        street: street.unwrap(),
+        seat,
+        action: play,
+      });
+    }
+  }
+  for pot in &ohh.pots {
+    for win in &pot.player_wins {
+      let seat = seat_of(win.player_id)?;
+      match history.collected.iter_mut().find(|c| c.seat == seat) {
+        Some(collected) => collected.amount += win.win_amount,
+        None => history.collected.push(Collected {
+          seat,
+          amount: win.win_amount,
+        }),
+      }
+    }
+  }
+  history.seats = seats;
+  history.check()?;
+  Ok(history)
+}
+
+pub fn to_json(history: &HandHistory) -> String {
+  let document = Document {
+    ohh: to_ohh(history),
+  };
+  serde_json::to_string_pretty(&document).unwrap()
+}
+
+pub fn from_json(json: &str) -> Result<HandHistory, &'static str> {
+  let document: Document =
+    serde_json::from_str(json).map_err(|_| "Invalid Open Hand History JSON.")?;
+  from_ohh(document.ohh)
+}
+
+// Every hand in a file, in order.
+pub fn read_all(text: &str) -> Result<Vec<HandHistory>, &'static str> {
+  serde_json::Deserializer::from_str(text)
+    .into_iter::<Document>()
+    .map(|document| from_ohh(document.map_err(|_| "Invalid Open Hand History JSON.")?.ohh))
+    .collect()
+}
+
+// Hands separated by a blank line, as the spec suggests for files.
+pub fn write_all(histories: &[HandHistory]) -> String {
+  let hands: Vec<String> = histories.iter().map(to_json).collect();
+  hands.join("\n\n") + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use serde_json::Value;
+
+  fn cards(s: &str) -> Vec<Card> {
+    s.split_whitespace().map(|c| c.parse().unwrap()).collect()
+  }
+
+  // Heads up: the button shoves the turn and is called, the big blind's
+  // side of the stacks is larger.
+  fn all_in_hand() -> HandHistory {
+    let play = |street, seat, action| Play {
+      street,
+      seat,
+      action,
+    };
+    HandHistory {
+      id: 42,
+      table: "Kerr Hall".to_string(),
+      max_seats: 2,
+      timestamp: 1639253700,
+      small_blind: 1,
+      big_blind: 2,
+      button: 0,
+      seats: vec![
+        Seat {
+          number: 1,
+          name: "Ann".to_string(),
+          stack: 100,
+          hole: Some(cards("AH AD").try_into().unwrap()),
+        },
+        Seat {
+          number: 2,
+          name: "Bo".to_string(),
+          stack: 300,
+          hole: Some(cards("KC QC").try_into().unwrap()),
+        },
+      ],
+      forced_bets: vec![
+        ForcedBet {
+          seat: 0,
+          post: Post::SmallBlind,
+          amount: 1,
+        },
+        ForcedBet {
+          seat: 1,
+          post: Post::BigBlind,
+          amount: 2,
+        },
+      ],
+      actions: vec![
+        play(Street::Preflop, 0, Action::Raise(6)),
+        play(Street::Preflop, 1, Action::Call(4)),
+        play(Street::Flop, 1, Action::Check),
+        play(Street::Flop, 0, Action::Bet(10)),
+        play(Street::Flop, 1, Action::Call(10)),
+        play(Street::Turn, 1, Action::Check),
+        play(Street::Turn, 0, Action::Bet(84)),
+        play(Street::Turn, 1, Action::Call(84)),
+      ],
+      board: cards("2C 7D 9H JS 3C"),
+      collected: vec![Collected {
+        seat: 0,
+        amount: 200,
+      }],
+    }
+  }
+
+  #[test]
+  fn test_write() {
+    let hand = all_in_hand();
+    assert_eq!(hand.replay(), Ok(vec![200, 0]));
+    let json: Value = serde_json::from_str(&to_json(&hand)).unwrap();
+    let ohh = &json["ohh"];
+    assert_eq!(ohh["spec_version"], SPEC_VERSION);
+    assert_eq!(ohh["start_date_utc"], "2021-12-11T20:15:00Z");
+    assert_eq!(ohh["dealer_seat"], 1);
+    assert_eq!(ohh["players"][1]["starting_stack"], 300);
+    let rounds = ohh["rounds"].as_array().unwrap();
+    let streets: Vec<&str> = rounds
+      .iter()
+      .map(|r| r["street"].as_str().unwrap())
+      .collect();
+    assert_eq!(streets, ["Preflop", "Flop", "Turn", "River", "Showdown"]);
+    assert_eq!(
+      rounds[0]["actions"][2]["cards"],
+      serde_json::json!(["Ah", "Ad"])
+    );
+    assert_eq!(rounds[2]["cards"], serde_json::json!(["Js"]));
+    assert_eq!(rounds[2]["actions"][1]["action"], "Bet");
+    assert_eq!(rounds[2]["actions"][1]["is_allin"], true);
+    assert_eq!(rounds[2]["actions"][2]["is_allin"], false);
+    assert_eq!(ohh["pots"][0]["amount"], 200);
+    assert_eq!(ohh["pots"][0]["player_wins"][0]["win_amount"], 200);
+  }
+  #[test]
+  fn test_round_trip() {
+    let hand = all_in_hand();
+    assert_eq!(from_json(&to_json(&hand)), Ok(hand.clone()));
+    let mut folded = hand.clone();
+    folded.actions.truncate(1);
+    folded.actions.push(Play {
+      street: Street::Preflop,
+      seat: 1,
+      action: Action::Fold,
+    });
+    folded.board.clear();
+    folded.seats[1].hole = None;
+    folded.collected[0].amount = 4;
+    assert_eq!(from_json(&to_json(&folded)), Ok(folded.clone()));
+    let file = write_all(&[hand.clone(), folded.clone()]);
+    assert_eq!(read_all(&file), Ok(vec![hand, folded]));
+  }
+  #[test]
+  fn test_side_pots() {
+    let mut hand = all_in_hand();
+    hand.seats[0].stack = 50;
+    hand.seats.push(Seat {
+      number: 3,
+      name: "Cy".to_string(),
+      stack: 300,
+      hole: None,
+    });
+    // everyone antes all they can and the rest is checked down
+    hand.forced_bets = (0..3)
+      .map(|seat| ForcedBet {
+        seat,
+        post: Post::Ante,
+        amount: 100,
+      })
+      .collect();
+    hand.actions.clear();
+    hand.seats[2].stack = 80;
+    assert_eq!(
+      pots(&hand),
+      vec![(150, vec![0, 1, 2]), (60, vec![1, 2]), (20, vec![1])]
+    );
+    hand.collected = vec![Collected {
+      seat: 1,
+      amount: 230,
+    }];
+    let json: Value = serde_json::from_str(&to_json(&hand)).unwrap();
+    let pots = json["ohh"]["pots"].as_array().unwrap();
+    assert_eq!(pots.len(), 3);
+    assert!(pots.iter().all(|p| p["player_wins"][0]["player_id"] == 1));
+    assert_eq!(read_all(&write_all(&[hand.clone()])), Ok(vec![hand]));
+  }
+  #[test]
+  fn test_read_errors() {
+    assert!(from_json("{}").is_err());
+    let json = to_json(&all_in_hand());
+    assert!(from_json(&json.replace("\"Holdem\"", "\"Omaha\"")).is_err());
+    assert!(from_json(&json.replace("\"Bet\"", "\"Dance\"")).is_err());
+    assert!(from_json(&json.replace("\"amount\": 84", "\"amount\": 84.5")).is_err());
+    assert!(from_json(&json.replace("\"9h\"", "\"9x\"")).is_err());
+
+    // rounds that don't agree with their cards
+    let edit = |change: &dyn Fn(&mut Value)| {
+      let mut value: Value = serde_json::from_str(&json).unwrap();
+      change(&mut value["ohh"]["rounds"]);
+      from_json(&value.to_string())
+    };
+    assert!(edit(&|_| {}).is_ok());
+    let no_flop = edit(&|rounds| rounds[1]["cards"] = serde_json::json!([]));
+    assert_eq!(no_flop, Err("Round has the wrong number of cards."));
+    assert!(edit(&|rounds| rounds[1]["cards"] = serde_json::json!(["2c"])).is_err());
+    assert!(edit(&|rounds| rounds[2]["cards"] = serde_json::json!(["Js", "3c"])).is_err());
+    let swapped = edit(&|rounds| {
+      let turn = rounds[2].clone();
+      rounds[2] = rounds[1].clone();
+      rounds[1] = turn;
+    });
+    assert!(swapped.is_err());
+    let low_raise = edit(&|rounds| {
+      let raise = rounds[0]["actions"]
+        .as_array_mut()
+        .unwrap()
+        .iter_mut()
+        .find(|a| a["action"] == "Raise")
+        .unwrap();
+      raise["amount"] = serde_json::json!(1);
+    });
+    assert_eq!(low_raise, Err("Raise is below the current bet."));
+  }
+}
@@ -0,0 +1,264 @@
+// Outs and draws for a hand on the flop or turn.
+//
+// An out is an unseen card that lifts the hand into a better Rank category.
+// Draws only count when a hole card takes part, so four hearts on the board
+// is not a flush draw for a hand without a heart.
+
+use crate::Card;
+use crate::Deal;
+use crate::Face;
+use crate::Rank;
+use crate::Street;
+use crate::Suit;
+use std::fmt;
+
+#[derive(Clone, Debug, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Draw {
+  FlushDraw,
+  // eight straight outs, including double gutshots
+  OpenEnded,
+  Gutshot,
+  // two running cards needed, flop only
+  BackdoorFlush,
+  BackdoorStraight,
+  // both hole cards above the board
+  Overcards,
+}
+
+impl fmt::Display for Draw {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "{}",
+      match self {
+        Self::FlushDraw => "flush draw",
+        Self::OpenEnded => "open-ended straight draw",
+        Self::Gutshot => "gutshot",
+        Self::BackdoorFlush => "backdoor flush draw",
+        Self::BackdoorStraight => "backdoor straight draw",
+        Self::Overcards => "overcards",
+      }
+    )
+  }
+}
+
+// A card to come and the hand it makes.
+#[derive(Clone, Debug, Copy, Eq, PartialEq)]
+pub struct Out {
+  pub card: Card,
+  pub rank: Rank,
+}
+
+// One bit per face from Two, with the Ace also set below the Two so it can
+// play low.
+fn face_bits(faces: impl Iterator<Item = Face>) -> u16 {
+  faces.fold(0, |bits, face| {
+    let bit = 1 << (face as u16 + 1);
+    if face.is_ace() {
+      bits | bit | 1
+    } else {
+      bits | bit
+    }
+  })
+}
+
+fn face_bit(face: Face) -> u16 {
+  face_bits([face].into_iter())
+}
+
+fn has_run(bits: u16) -> bool {
+  (0..=9).any(|low| (bits >> low) & 0b11111 == 0b11111)
+}
+
+fn suit_counts(cards: &[Card]) -> [usize; 4] {
+  let mut counts = [0; 4];
+  for card in cards {
+    counts[card.suit() as usize] += 1;
+  }
+  counts
+}
+
+fn playing(deal: &Deal) -> Result<(), &'static str> {
+  if deal.street() == Street::River {
+    return Err("No cards to come.");
+  }
+  Ok(())
+}
+
+// Every unseen card that improves the hand's category.
+pub fn outs(deal: &Deal) -> Result<Vec<Out>, &'static str> {
+  playing(deal)?;
+  let now = deal.rank().category();
+  Ok(
+    deal
+      .unseen()
+      .into_iter()
+      .map(|card| Out {
+        card,
+        rank: deal.with(card).unwrap().rank(),
+      })
+      .filter(|out| out.rank.category() > now)
+      .collect(),
+  )
+}
+
+// The outs that also leave the hand ahead of the villain's.
+pub fn clean_outs(deal: &Deal, villain: [Card; 2]) -> Result<Vec<Out>, &'static str> {
+  if villain.iter().any(|c| deal.contains(*c)) {
+    return Err("Villain's cards are already dealt.");
+  }
+  let theirs = Deal::partial(villain, deal.community())?;
+  Ok(
+    outs(deal)?
+      .into_iter()
+      .filter(|out| !villain.contains(&out.card))
+      .filter(|out| out.rank > theirs.with(out.card).unwrap().rank())
+      .collect(),
+  )
+}
+
+pub fn draws(deal: &Deal) -> Result<Vec<Draw>, &'static str> {
+  playing(deal)?;
+  let mut found = Vec::new();
+  let cards = deal.combine();
+  let board = deal.community();
+  let flop = deal.street() == Street::Flop;
+
+  let mine = suit_counts(&cards);
+  let theirs = suit_counts(board);
+  if !mine.contains(&5) {
+    for suit in Suit::all() {
+      let (mine, theirs) = (mine[suit as usize], theirs[suit as usize]);
+      if mine == 4 && theirs < 4 {
+        found.push(Draw::FlushDraw);
+      } else if flop && mine == 3 && theirs < 3 {
+        found.push(Draw::BackdoorFlush);
+      }
+    }
+  }
+
+  let all = face_bits(cards.iter().map(|c| c.face()));
+  let shared = face_bits(board.iter().map(|c| c.face()));
+  if !has_run(all) {
+    let completes = |extra: u16| has_run(all | extra) && !has_run(shared | extra);
+    let singles = Face::all()
+      .into_iter()
+      .filter(|&f| completes(face_bit(f)))
+      .count();
+    if singles >= 2 {
+      found.push(Draw::OpenEnded);
+    } else if singles == 1 {
+      found.push(Draw::Gutshot);
+    } else if flop {
+      let backdoor = Face::all().into_iter().any(|a| {
+        Face::all()
+          .into_iter()
+          .any(|b| a < b && completes(face_bit(a) | face_bit(b)))
+      });
+      if backdoor {
+        found.push(Draw::BackdoorStraight);
+      }
+    }
+  }
+
+  let [a, b] = deal.hole();
+  let top = board.iter().map(|c| c.face()).max().unwrap();
+  if a.face() != b.face() && a.face().min(b.face()) > top {
+    found.push(Draw::Overcards);
+  }
+  found.sort();
+  Ok(found)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn deal(hole: &str, board: &str) -> Deal {
+    let hole: Vec<Card> = hole
+      .split_whitespace()
+      .map(|c| c.parse().unwrap())
+      .collect();
+    let board: Vec<Card> = board
+      .split_whitespace()
+      .map(|c| c.parse().unwrap())
+      .collect();
+    Deal::partial(hole.try_into().unwrap(), &board).unwrap()
+  }
+
+  #[test]
+  fn test_flush_draw_outs() {
+    // nine hearts for a flush, and any other ace, queen, deuce, seven or jack
+    // for a pair
+    let d = deal("AH QH", "2H 7H JS");
+    let outs = outs(&d).unwrap();
+    assert_eq!(outs.len(), 9 + 3 + 3 + 3 + 3 + 2);
+    let flushes = outs.iter().filter(|o| o.rank.category() == 5).count();
+    assert_eq!(flushes, 9);
+    assert_eq!(
+      draws(&d).unwrap(),
+      vec![Draw::FlushDraw, Draw::BackdoorStraight, Draw::Overcards]
+    );
+  }
+  #[test]
+  fn test_straight_draws() {
+    assert_eq!(
+      draws(&deal("9C 8D", "TH 7S 2C")).unwrap(),
+      vec![Draw::OpenEnded]
+    );
+    assert_eq!(
+      draws(&deal("9C 8D", "JH 7S 2C KD")).unwrap(),
+      vec![Draw::Gutshot]
+    );
+    // a double gutshot has eight outs too
+    assert_eq!(
+      draws(&deal("9C 7D", "JH 5S 8C 2D")).unwrap(),
+      vec![Draw::OpenEnded]
+    );
+    // the wheel
+    assert_eq!(
+      draws(&deal("AC 2D", "3H 4S KC")).unwrap(),
+      vec![Draw::Gutshot]
+    );
+    // the straight draw is all on the board
+    assert_eq!(draws(&deal("2C 2D", "9H TS JC QD")).unwrap(), vec![]);
+  }
+  #[test]
+  fn test_backdoors_and_overcards() {
+    assert_eq!(
+      draws(&deal("AH KH", "QH 7C 2S")).unwrap(),
+      vec![Draw::BackdoorFlush, Draw::BackdoorStraight, Draw::Overcards]
+    );
+    // no backdoors on the turn
+    assert_eq!(
+      draws(&deal("AH KH", "QH 7C 2S 3D")).unwrap(),
+      vec![Draw::Overcards]
+    );
+    assert_eq!(draws(&deal("KH KD", "QH 7C 2S")).unwrap(), vec![]);
+  }
+  #[test]
+  fn test_clean_outs() {
+    // against a set, hearts that pair the board give the villain a full
+    // house, and pairing ourselves is no good; seven hearts and three tens
+    // for Broadway are left
+    let d = deal("AH QH", "2H 7H JS KD");
+    let villain = ["JC", "JD"].map(|c| c.parse().unwrap());
+    assert!(outs(&d).unwrap().len() > 10);
+    let clean = clean_outs(&d, villain).unwrap();
+    let cards: Vec<String> = clean.iter().map(|o| o.card.to_string()).collect();
+    assert_eq!(cards.len(), 10);
+    assert!(cards.contains(&"9H".to_string()));
+    assert!(cards.contains(&"TC".to_string()));
+    assert!(!cards.contains(&"KH".to_string()));
+    assert!(!cards.contains(&"JH".to_string()));
+    assert!(!cards.contains(&"AC".to_string()));
+  }
+  #[test]
+  fn test_errors() {
+    let river = deal("AH QH", "2H 7H JS KD 3C");
+    assert!(outs(&river).is_err());
+    assert!(draws(&river).is_err());
+    let turn = deal("AH QH", "2H 7H JS KD");
+    assert!(clean_outs(&turn, ["AH", "2C"].map(|c| c.parse().unwrap())).is_err());
+  }
+}
@@ -16,6 +16,6 @@ pub fn deal(perm: [u32; 9]) -> Vec<String> {
   let best_hand_p1 = p1.best_hand();
   let best_hand_p2 = p2.best_hand();
   let winner = best_hand_p1.max(best_hand_p2);
-  // return vec as a string, use .as_str_vec()
-  winner.as_str_vec()
+  // return only the cards that make the hand, as strings
+  winner.made_str_vec()
 }
@@ -0,0 +1,296 @@
+// Heads-up all-in equity of every starting hand class against every other,
+// and against one or more random hands, estimated by simulation.
+//
+// Hands are looked up by class (AKs, AKo, TT), so each entry is the average
+// over every way of giving the two classes suits without sharing a card.
+//
+// The file format is a header, "PFEQ", the version, the trials per entry
+// (u32) and the number of opponents (u8), followed by the 169x169 heads-up
+// table row by row and the 169 rows of multiway equities. Equities are
+// stored as u16 fractions of 65535, little endian.
+
+use crate::Card;
+use crate::Combo;
+use crate::Deal;
+use crate::HandClass;
+use crate::Rank;
+use crate::Rng;
+use std::cmp::Ordering;
+use std::fs;
+use std::path::Path;
+
+const MAGIC: &[u8; 4] = b"PFEQ";
+pub const VERSION: u16 = 1;
+const HEADER: usize = 4 + 2 + 4 + 1;
+const CLASSES: usize = 169;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PreflopTable {
+  trials: u32,
+  opponents: u8,
+  heads_up: Vec<u16>,
+  multiway: Vec<u16>,
+}
+
+fn quantize(equity: f64) -> u16 {
+  (equity.clamp(0.0, 1.0) * u16::MAX as f64).round() as u16
+}
+
+fn unquantize(value: u16) -> f64 {
+  value as f64 / u16::MAX as f64
+}
+
+// k random cards that are not dead
+fn draw(rng: &mut Rng, dead: &[Card], k: usize) -> Vec<Card> {
+  let all = Card::all();
+  let mut cards = Vec::with_capacity(k);
+  while cards.len() < k {
+    let card = all[rng.below(52)];
+    if !dead.contains(&card) && !cards.contains(&card) {
+      cards.push(card);
+    }
+  }
+  cards
+}
+
+fn pick(rng: &mut Rng, combos: &[Combo], dead: &[Card]) -> Combo {
+  loop {
+    let combo = combos[rng.below(combos.len())];
+    if !combo.is_blocked(dead) {
+      return combo;
+    }
+  }
+}
+
+fn rank(hole: [Card; 2], board: &[Card]) -> Rank {
+  Deal::partial(hole, board).unwrap().rank()
+}
+
+impl PreflopTable {
+  // Simulates every matchup, and each class against 1 to `opponents` random
+  // hands, `trials` times.
+  pub fn build(trials: u32, opponents: u8, seed: u64) -> Result<Self, &'static str> {
+    if trials == 0 {
+      return Err("A preflop table needs at least one trial.");
+    }
+    let mut rng = Rng::new(seed);
+    let combos: Vec<Vec<Combo>> = HandClass::all().iter().map(|c| c.combos()).collect();
+    let mut heads_up = vec![0; CLASSES * CLASSES];
+    for a in 0..CLASSES {
+      // a class against itself is a coin flip
+      heads_up[a * CLASSES + a] = quantize(0.5);
+      for b in a + 1..CLASSES {
+        let mut share = 0.0;
+        for _ in 0..trials {
+          let hero = pick(&mut rng, &combos[a], &[]);
+          let villain = pick(&mut rng, &combos[b], &hero.cards());
+          let dead = [hero.cards(), villain.cards()].concat();
+          let board = draw(&mut rng, &dead, 5);
+          share += match rank(hero.cards(), &board).cmp(&rank(villain.cards(), &board)) {
+            Ordering::Greater => 1.0,
+            Ordering::Equal => 0.5,
+            Ordering::Less => 0.0,
+          };
+        }
+        let equity = share / trials as f64;
+        heads_up[a * CLASSES + b] = quantize(equity);
+        heads_up[b * CLASSES + a] = quantize(1.0 - equity);
+      }
+    }
+    let mut multiway = vec![0; CLASSES * opponents as usize];
+    for (a, class) in combos.iter().enumerate() {
+      for n in 1..=opponents as usize {
+        let mut share = 0.0;
+        for _ in 0..trials {
+          let hero = pick(&mut rng, class, &[]);
+          let cards = draw(&mut rng, &hero.cards(), 2 * n + 5);
+          let board = &cards[2 * n..];
+          let mine = rank(hero.cards(), board);
+          let best = (0..n)
+            .map(|i| rank([cards[2 * i], cards[2 * i + 1]], board))
+            .max()
+            .unwrap();
+          if mine > best {
+            share += 1.0;
+          } else if mine == best {
+            let tied = (0..n)
+              .filter(|&i| rank([cards[2 * i], cards[2 * i + 1]], board) == mine)
+              .count();
+            share += 1.0 / (tied + 1) as f64;
+          }
+        }
+        multiway[a * opponents as usize + n - 1] = quantize(share / trials as f64);
+      }
+    }
+    Ok(Self {
+      trials,
+      opponents,
+      heads_up,
+      multiway,
+    })
+  }
+  pub fn trials(&self) -> u32 {
+    self.trials
+  }
+  // the most random opponents there are equities for
+  pub fn opponents(&self) -> u8 {
+    self.opponents
+  }
+  pub fn class_equity(&self, hero: &HandClass, villain: &HandClass) -> f64 {
+    unquantize(self.heads_up[hero.index() * CLASSES + villain.index()])
+  }
+  // hero's equity against villain, by their classes
+  pub fn equity(&self, hero: [Card; 2], villain: [Card; 2]) -> Result<f64, &'static str> {
+    let hero = Combo::try_new(hero[0], hero[1])?;
+    let villain = Combo::try_new(villain[0], villain[1])?;
+    if hero.overlaps(&villain) {
+      return Err("Hands share a card.");
+    }
+    Ok(self.class_equity(&HandClass::from(hero), &HandClass::from(villain)))
+  }
+  pub fn class_vs_random(&self, hero: &HandClass, opponents: usize) -> Option<f64> {
+    if opponents == 0 || opponents > self.opponents as usize {
+      return None;
+    }
+    let value = self.multiway[hero.index() * self.opponents as usize + opponents - 1];
+    Some(unquantize(value))
+  }
+  pub fn vs_random(&self, hero: [Card; 2], opponents: usize) -> Option<f64> {
+    let combo = Combo::try_new(hero[0], hero[1]).ok()?;
+    self.class_vs_random(&HandClass::from(combo), opponents)
+  }
+
+  pub fn to_bytes(&self) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(HEADER + 2 * (self.heads_up.len() + self.multiway.len()));
+    bytes.extend_from_slice(MAGIC);
+    bytes.extend_from_slice(&VERSION.to_le_bytes());
+    bytes.extend_from_slice(&self.trials.to_le_bytes());
+    bytes.push(self.opponents);
+    for value in self.heads_up.iter().chain(self.multiway.iter()) {
+      bytes.extend_from_slice(&value.to_le_bytes());
+    }
+    bytes
+  }
+  pub fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+    if bytes.len() < HEADER || &bytes[..4] != MAGIC {
+      return Err("Not a preflop table.");
+    }
+    if u16::from_le_bytes([bytes[4], bytes[5]]) != VERSION {
+      return Err("Unsupported preflop table version.");
+    }
+    let trials = u32::from_le_bytes(bytes[6..10].try_into().unwrap());
+    if trials == 0 {
+      return Err("Preflop table has no trials.");
+    }
+    let opponents = bytes[10];
+    let entries = CLASSES * CLASSES + CLASSES * opponents as usize;
+    if bytes.len() != HEADER + 2 * entries {
+      return Err("Preflop table is the wrong size.");
+    }
+    let mut values: Vec<u16> = bytes[HEADER..]
+      .chunks_exact(2)
+      .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+      .collect();
+    let multiway = values.split_off(CLASSES * CLASSES);
+    Ok(Self {
+      trials,
+      opponents,
+      heads_up: values,
+      multiway,
+    })
+  }
+  pub fn save(&self, path: &Path) -> Result<(), &'static str> {
+    fs::write(path, self.to_bytes()).map_err(|_| "Could not write preflop table.")
+  }
+  pub fn load(path: &Path) -> Result<Self, &'static str> {
+    let bytes = fs::read(path).map_err(|_| "Could not read preflop table.")?;
+    Self::from_bytes(&bytes)
+  }
+  // Reads the table cached at path, or builds and caches it if there isn't
+  // a usable one. Fails if the new table can't be cached.
+  pub fn load_or_build(
+    path: &Path,
+    trials: u32,
+    opponents: u8,
+    seed: u64,
+  ) -> Result<Self, &'static str> {
+    match Self::load(path) {
+      Ok(table) if table.trials >= trials && table.opponents >= opponents => Ok(table),
+      _ => {
+        let table = Self::build(trials, opponents, seed)?;
+        table.save(path)?;
+        Ok(table)
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn cards(s: &str) -> [Card; 2] {
+    [s[0..2].parse().unwrap(), s[2..4].parse().unwrap()]
+  }
+
+  #[test]
+  fn test_build() {
+    let table = PreflopTable::build(24, 2, 37).unwrap();
+    let aa: HandClass = "AA".parse().unwrap();
+    let seven_two: HandClass = "72o".parse().unwrap();
+    assert!(table.class_equity(&aa, &seven_two) > 0.7);
+    assert!(table.class_equity(&seven_two, &aa) < 0.3);
+    // every pair of entries sums to one, give or take rounding
+    for a in HandClass::all() {
+      for b in HandClass::all() {
+        let sum = table.class_equity(&a, &b) + table.class_equity(&b, &a);
+        assert!((sum - 1.0).abs() < 1e-4);
+      }
+    }
+    // suits don't matter once hands are classed
+    assert_eq!(
+      table.equity(cards("AHAS"), cards("7C2D")),
+      Ok(table.class_equity(&aa, &seven_two))
+    );
+    assert!(table.equity(cards("AHAH"), cards("7C2D")).is_err());
+    assert_eq!(
+      table.equity(cards("AHKS"), cards("AHKD")),
+      Err("Hands share a card.")
+    );
+    assert_eq!(table.vs_random(cards("AHAH"), 1), None);
+    let one = table.vs_random(cards("AHAS"), 1).unwrap();
+    let two = table.vs_random(cards("ASAH"), 2).unwrap();
+    assert!(one > two && two > 0.5, "{} {}", one, two);
+    assert_eq!(table.vs_random(cards("AHAS"), 3), None);
+    assert_eq!(table.vs_random(cards("AHAS"), 0), None);
+  }
+  #[test]
+  fn test_bytes() {
+    let table = PreflopTable::build(1, 1, 1).unwrap();
+    let bytes = table.to_bytes();
+    assert_eq!(bytes.len(), 11 + 2 * (169 * 169 + 169));
+    assert_eq!(PreflopTable::from_bytes(&bytes), Ok(table));
+    let mut old = bytes.clone();
+    old[4] = 0;
+    assert!(PreflopTable::from_bytes(&old).is_err());
+    assert!(PreflopTable::from_bytes(&bytes[..100]).is_err());
+    assert!(PreflopTable::from_bytes(b"nope").is_err());
+    let mut untried = bytes.clone();
+    untried[6..10].copy_from_slice(&0u32.to_le_bytes());
+    assert!(PreflopTable::from_bytes(&untried).is_err());
+    assert!(PreflopTable::build(0, 1, 1).is_err());
+  }
+  #[test]
+  fn test_cache() {
+    let path = std::env::temp_dir().join(format!("preflop-{}.bin", std::process::id()));
+    let built = PreflopTable::load_or_build(&path, 1, 1, 5).unwrap();
+    assert_eq!(PreflopTable::load(&path), Ok(built.clone()));
+    assert_eq!(PreflopTable::load_or_build(&path, 1, 1, 6), Ok(built));
+    fs::remove_file(&path).unwrap();
+    let unwritable = std::env::temp_dir().join("no-such-dir").join("preflop.bin");
+    assert_eq!(
+      PreflopTable::load_or_build(&unwritable, 1, 1, 5),
+      Err("Could not write preflop table.")
+    );
+  }
+}
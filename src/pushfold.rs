@@ -0,0 +1,150 @@
+// Heads-up push or fold: the small blind shoves all in or folds, and the big
+// blind calls or folds. Both strategies are found by fictitious play, each
+// side repeatedly best responding to the other's average strategy, which
+// converges on the Nash equilibrium of the game.
+//
+// Amounts are in big blinds: the small blind posts 0.5 and the big blind 1,
+// and both start with the effective stack.
+
+use crate::preflop::PreflopTable;
+use crate::Grid;
+use crate::HandClass;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PushFold {
+  pub stack: f64,
+  // how often each class shoves from the small blind
+  pub push: Grid,
+  // how often each class calls a shove in the big blind
+  pub call: Grid,
+  // the small blind's expected winnings per hand
+  pub value: f64,
+}
+
+// Combo pairs between two classes that don't share a card.
+fn pair_counts(classes: &[HandClass]) -> Vec<Vec<f64>> {
+  let combos: Vec<_> = classes.iter().map(|c| c.combos()).collect();
+  combos
+    .iter()
+    .map(|a| {
+      combos
+        .iter()
+        .map(|b| {
+          a.iter()
+            .flat_map(|x| b.iter().filter(move |y| !x.overlaps(y)))
+            .count() as f64
+        })
+        .collect()
+    })
+    .collect()
+}
+
+struct Game {
+  stack: f64,
+  equity: Vec<Vec<f64>>,
+  pairs: Vec<Vec<f64>>,
+}
+
+impl Game {
+  // the small blind's winnings shoving hand a, against a calling strategy
+  fn push_value(&self, a: usize, call: &[f64]) -> f64 {
+    let (mut dealt, mut value) = (0.0, 0.0);
+    for (b, &calls) in call.iter().enumerate() {
+      let pairs = self.pairs[a][b];
+      dealt += pairs;
+      value += pairs * (calls * (2.0 * self.equity[a][b] - 1.0) * self.stack + (1.0 - calls));
+    }
+    value / dealt
+  }
+  // the big blind's winnings calling with hand b, against a shoving strategy
+  fn call_value(&self, b: usize, push: &[f64]) -> Option<f64> {
+    let (mut pushed, mut value) = (0.0, 0.0);
+    for (a, &pushes) in push.iter().enumerate() {
+      let weight = self.pairs[a][b] * pushes;
+      pushed += weight;
+      value += weight * (2.0 * self.equity[b][a] - 1.0) * self.stack;
+    }
+    (pushed > 0.0).then(|| value / pushed)
+  }
+}
+
+fn grid(classes: &[HandClass], strategy: &[f64]) -> Grid {
+  let mut grid = Grid::new();
+  for (class, &weight) in classes.iter().zip(strategy) {
+    grid.set_weight(class, weight);
+  }
+  grid
+}
+
+pub fn solve(
+  table: &PreflopTable,
+  stack: f64,
+  iterations: usize,
+) -> Result<PushFold, &'static str> {
+  if stack.is_nan() || stack < 1.0 {
+    return Err("The stack must cover the big blind.");
+  }
+  let classes = HandClass::all();
+  let game = Game {
+    stack,
+    equity: classes
+      .iter()
+      .map(|a| classes.iter().map(|b| table.class_equity(a, b)).collect())
+      .collect(),
+    pairs: pair_counts(&classes),
+  };
+  // the first best responses replace these starting guesses
+  let mut push = vec![1.0; classes.len()];
+  let mut call = vec![1.0; classes.len()];
+  for t in 1..=iterations {
+    let step = 1.0 / t as f64;
+    let best_push: Vec<f64> = (0..classes.len())
+      .map(|a| (game.push_value(a, &call) > -0.5) as u8 as f64)
+      .collect();
+    let best_call: Vec<f64> = (0..classes.len())
+      .map(|b| (game.call_value(b, &push).is_some_and(|v| v > -1.0)) as u8 as f64)
+      .collect();
+    for (average, best) in push.iter_mut().zip(best_push) {
+      *average += step * (best - *average);
+    }
+    for (average, best) in call.iter_mut().zip(best_call) {
+      *average += step * (best - *average);
+    }
+  }
+  let combos: Vec<f64> = classes.iter().map(|c| c.combos().len() as f64).collect();
+  let value = (0..classes.len())
+    .map(|a| combos[a] * (push[a] * game.push_value(a, &call) - (1.0 - push[a]) * 0.5))
+    .sum::<f64>()
+    / 1326.0;
+  Ok(PushFold {
+    stack,
+    push: grid(&classes, &push),
+    call: grid(&classes, &call),
+    value,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_solve() {
+    let table = PreflopTable::build(8, 1, 39).unwrap();
+    let class = |s: &str| s.parse::<HandClass>().unwrap();
+
+    let short = solve(&table, 1.5, 200).unwrap();
+    assert!(short.push.coverage() > 0.9, "{}", short.push.coverage());
+
+    let deep = solve(&table, 20.0, 200).unwrap();
+    assert_eq!(deep.push.weight(&class("AA")), 1.0);
+    assert_eq!(deep.call.weight(&class("AA")), 1.0);
+    assert!(deep.push.weight(&class("72o")) < 0.1);
+    assert!(deep.call.coverage() < deep.push.coverage());
+    assert!(deep.push.coverage() < short.push.coverage());
+    // shoving is worth more than folding every hand
+    assert!(deep.value > -0.5);
+
+    assert!(solve(&table, 0.5, 10).is_err());
+  }
+}
@@ -0,0 +1,427 @@
+use crate::Card;
+use crate::Combo;
+use crate::Face;
+use crate::Suit;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+// One of the 169 preflop hand types: a pair, or two faces suited or offsuit.
+#[derive(Clone, Debug, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct HandClass {
+  high: Face,
+  low: Face,
+  suited: bool,
+}
+
+impl HandClass {
+  pub fn new(a: Face, b: Face, suited: bool) -> Self {
+    Self {
+      high: a.max(b),
+      low: a.min(b),
+      suited: suited && a != b,
+    }
+  }
+  pub fn high(&self) -> Face {
+    self.high
+  }
+  pub fn low(&self) -> Face {
+    self.low
+  }
+  pub fn is_pair(&self) -> bool {
+    self.high == self.low
+  }
+  pub fn is_suited(&self) -> bool {
+    self.suited
+  }
+  // 6 for a pair, 4 suited, 12 offsuit
+  pub fn combos(&self) -> Vec<Combo> {
+    let mut combos = Vec::new();
+    for a in Suit::all() {
+      for b in Suit::all() {
+        let keep = if self.is_pair() {
+          a < b
+        } else {
+          (a == b) == self.suited
+        };
+        if keep {
+          combos.push(Combo::new(Card::new(self.high, a), Card::new(self.low, b)));
+        }
+      }
+    }
+    combos.sort();
+    combos
+  }
+  pub fn all() -> Vec<Self> {
+    let mut classes = Vec::with_capacity(169);
+    for high in Face::all() {
+      for low in Face::all() {
+        if low < high {
+          classes.push(Self::new(high, low, true));
+          classes.push(Self::new(high, low, false));
+        } else if low == high {
+          classes.push(Self::new(high, low, false));
+        }
+      }
+    }
+    classes.sort();
+    classes
+  }
+  // position in all(), 0 to 168
+  pub fn index(&self) -> usize {
+    let (high, low) = (self.high as usize, self.low as usize);
+    high * high + 2 * low + self.suited as usize
+  }
+  pub fn from_index(index: usize) -> Option<Self> {
+    if index >= 169 {
+      return None;
+    }
+    let high = (0..13).rev().find(|h| h * h <= index).unwrap();
+    let rest = index - high * high;
+    let faces = Face::all();
+    Some(Self::new(faces[high], faces[rest / 2], rest % 2 == 1))
+  }
+}
+
+impl From<Combo> for HandClass {
+  fn from(combo: Combo) -> Self {
+    let [a, b] = combo.cards();
+    Self::new(a.face(), b.face(), combo.is_suited())
+  }
+}
+
+impl fmt::Display for HandClass {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match (self.is_pair(), self.suited) {
+      (true, _) => write!(f, "{}{}", self.high, self.low),
+      (false, true) => write!(f, "{}{}s", self.high, self.low),
+      (false, false) => write!(f, "{}{}o", self.high, self.low),
+    }
+  }
+}
+
+impl FromStr for HandClass {
+  type Err = &'static str;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match pattern(s)? {
+      (high, low, _) if high == low => Ok(Self::new(high, low, false)),
+      (high, low, Some(suited)) => Ok(Self::new(high, low, suited)),
+      _ => Err("Hand class needs an s or o suffix."),
+    }
+  }
+}
+
+// "AK", "AKs", "AKo" or "TT" as two faces and whether it's suited, if given
+fn pattern(s: &str) -> Result<(Face, Face, Option<bool>), &'static str> {
+  if !s.is_ascii() || !(2..=3).contains(&s.len()) {
+    return Err("Invalid hand.");
+  }
+  let a: Face = s[0..1].parse()?;
+  let b: Face = s[1..2].parse()?;
+  let suited = match &s[2..] {
+    "" => None,
+    "s" if a != b => Some(true),
+    "o" if a != b => Some(false),
+    _ => return Err("Invalid hand."),
+  };
+  Ok((a.max(b), a.min(b), suited))
+}
+
+fn classes(high: Face, low: Face, suited: Option<bool>) -> Vec<HandClass> {
+  match suited {
+    _ if high == low => vec![HandClass::new(high, low, false)],
+    Some(suited) => vec![HandClass::new(high, low, suited)],
+    None => vec![
+      HandClass::new(high, low, true),
+      HandClass::new(high, low, false),
+    ],
+  }
+}
+
+fn faces_between(a: Face, b: Face) -> impl Iterator<Item = Face> {
+  Face::all()
+    .into_iter()
+    .filter(move |&f| f >= a.min(b) && f <= a.max(b))
+}
+
+fn parse_token(token: &str) -> Result<Vec<Combo>, &'static str> {
+  if let Ok(combo) = token.parse::<Combo>() {
+    return Ok(vec![combo]);
+  }
+  let classes = if let Some((from, to)) = token.split_once('-') {
+    let (high, low, suited) = pattern(from)?;
+    match pattern(to)? {
+      (h, l, _) if high == low && h == l => faces_between(high, h)
+        .flat_map(|f| classes(f, f, None))
+        .collect(),
+      (h, l, s) if high != low && h == high && l != h && s == suited => faces_between(low, l)
+        .flat_map(|f| classes(high, f, suited))
+        .collect(),
+      _ => return Err("Invalid span."),
+    }
+  } else if let Some(base) = token.strip_suffix('+') {
+    let (high, low, suited) = pattern(base)?;
+    if high == low {
+      faces_between(low, Face::Ace)
+        .flat_map(|f| classes(f, f, None))
+        .collect()
+    } else {
+      faces_between(low, high)
+        .filter(|&f| f != high)
+        .flat_map(|f| classes(high, f, suited))
+        .collect()
+    }
+  } else {
+    let (high, low, suited) = pattern(token)?;
+    classes(high, low, suited)
+  };
+  Ok(classes.iter().flat_map(|c| c.combos()).collect())
+}
+
+// A weighted set of starting hands, e.g. "TT+, A2s-A5s, KQo, AA:0.5, AhKh".
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Range {
+  combos: BTreeMap<Combo, f64>,
+}
+
+impl Range {
+  pub fn new() -> Self {
+    Self::default()
+  }
+  pub fn insert(&mut self, combo: Combo, weight: f64) {
+    if weight > 0.0 {
+      self.combos.insert(combo, weight);
+    } else {
+      self.combos.remove(&combo);
+    }
+  }
+  pub fn weight(&self, combo: &Combo) -> f64 {
+    self.combos.get(combo).copied().unwrap_or(0.0)
+  }
+  pub fn combos(&self) -> impl Iterator<Item = (Combo, f64)> + '_ {
+    self.combos.iter().map(|(&c, &w)| (c, w))
+  }
+  pub fn len(&self) -> usize {
+    self.combos.len()
+  }
+  pub fn is_empty(&self) -> bool {
+    self.combos.is_empty()
+  }
+  // sum of the weights, i.e. the number of combos counting partial ones
+  pub fn total_weight(&self) -> f64 {
+    // from 0.0, as an empty f64 sum is -0.0
+    self.combos.values().fold(0.0, |total, w| total + w)
+  }
+  // drops combos that use any of the known cards
+  pub fn remove_blocked(&mut self, dead: &[Card]) {
+    self.combos.retain(|combo, _| !combo.is_blocked(dead));
+  }
+  pub fn without(&self, dead: &[Card]) -> Self {
+    let mut range = self.clone();
+    range.remove_blocked(dead);
+    range
+  }
+  // the shared weight of a class, if every combo of it is in the range with
+  // the same weight
+  pub fn class_weight(&self, class: &HandClass) -> Option<f64> {
+    let combos = class.combos();
+    let weight = self.combos.get(&combos[0]).copied()?;
+    combos
+      .iter()
+      .all(|c| self.combos.get(c) == Some(&weight))
+      .then_some(weight)
+  }
+}
+
+impl FromStr for Range {
+  type Err = &'static str;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let mut range = Self::new();
+    for token in s.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+      let (hands, weight) = match token.split_once(':') {
+        Some((hands, weight)) => {
+          let weight: f64 = weight.trim().parse().map_err(|_| "Invalid weight.")?;
+          if !(0.0..=1.0).contains(&weight) {
+            return Err("Weights must be between 0 and 1.");
+          }
+          (hands.trim(), weight)
+        }
+        None => (token, 1.0),
+      };
+      for combo in parse_token(hands)? {
+        range.insert(combo, weight);
+      }
+    }
+    Ok(range)
+  }
+}
+
+fn with_weight(hands: String, weight: f64) -> String {
+  if weight == 1.0 {
+    hands
+  } else {
+    format!("{}:{}", hands, weight)
+  }
+}
+
+// Writes runs of classes that share a weight in the shortest notation:
+// "TT+", "22-77", "ATs+", "A2s-A5s" or a single class.
+fn compress(run: &[HandClass]) -> String {
+  let (first, last) = (run[0], run[run.len() - 1]);
+  if run.len() == 1 {
+    return first.to_string();
+  }
+  let reaches_top = if first.is_pair() {
+    first.high == Face::Ace
+  } else {
+    Face::all()[first.high as usize - 1] == first.low
+  };
+  if reaches_top {
+    format!("{}+", last)
+  } else {
+    format!("{}-{}", last, first)
+  }
+}
+
+impl fmt::Display for Range {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let mut parts = Vec::new();
+    let mut listed = Vec::new();
+    // pairs, then suited and offsuit hands for each high card, best first
+    let mut groups: Vec<Vec<HandClass>> = vec![Face::all()
+      .iter()
+      .rev()
+      .map(|&f| HandClass::new(f, f, false))
+      .collect()];
+    for suited in [true, false] {
+      for high in Face::all().into_iter().rev() {
+        groups.push(
+          Face::all()
+            .into_iter()
+            .rev()
+            .filter(|&low| low < high)
+            .map(|low| HandClass::new(high, low, suited))
+            .collect(),
+        );
+      }
+    }
+    for group in groups {
+      let mut run: Vec<HandClass> = Vec::new();
+      let mut run_weight = 0.0;
+      for class in group {
+        let weight = self.class_weight(&class);
+        let continues = match (run.last(), weight) {
+          (Some(last), Some(w)) => w == run_weight && last.low as usize == class.low as usize + 1,
+          _ => false,
+        };
+        if !continues && !run.is_empty() {
+          parts.push(with_weight(compress(&run), run_weight));
+          run.clear();
+        }
+        if let Some(w) = weight {
+          run.push(class);
+          run_weight = w;
+          listed.extend(class.combos());
+        }
+      }
+      if !run.is_empty() {
+        parts.push(with_weight(compress(&run), run_weight));
+      }
+    }
+    for (combo, weight) in self.combos() {
+      if !listed.contains(&combo) {
+        parts.push(with_weight(combo.to_string(), weight));
+      }
+    }
+    write!(f, "{}", parts.join(", "))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn range(s: &str) -> Range {
+    s.parse().unwrap()
+  }
+
+  #[test]
+  fn test_hand_class() {
+    assert_eq!(HandClass::all().len(), 169);
+    let class: HandClass = "KAs".parse().unwrap();
+    assert_eq!(class.to_string(), "AKs");
+    assert_eq!(class.combos().len(), 4);
+    assert_eq!("AKo".parse::<HandClass>().unwrap().combos().len(), 12);
+    assert_eq!("77".parse::<HandClass>().unwrap().combos().len(), 6);
+    assert!("AK".parse::<HandClass>().is_err());
+    assert!("77s".parse::<HandClass>().is_err());
+    let combo: Combo = "Th9h".parse().unwrap();
+    assert_eq!(HandClass::from(combo).to_string(), "T9s");
+  }
+  #[test]
+  fn test_parse() {
+    assert_eq!(range("AKs").len(), 4);
+    assert_eq!(range("AK").len(), 16);
+    assert_eq!(range("KQo").len(), 12);
+    assert_eq!(range("TT+").len(), 30);
+    assert_eq!(range("22-77").len(), 36);
+    assert_eq!(range("77-22").len(), 36);
+    assert_eq!(range("A2s-A5s").len(), 16);
+    assert_eq!(range("ATs+").len(), 16);
+    assert_eq!(range("AhKh, AsKs").len(), 2);
+    let weighted = range("AA:0.5, KK");
+    assert_eq!(weighted.weight(&"AhAs".parse().unwrap()), 0.5);
+    assert_eq!(weighted.weight(&"KhKs".parse().unwrap()), 1.0);
+    assert_eq!(weighted.total_weight(), 9.0);
+    assert_eq!(range("").len(), 0);
+    assert_eq!(range("").total_weight().to_string(), "0");
+  }
+  #[test]
+  fn test_parse_errors() {
+    assert!("AKx".parse::<Range>().is_err());
+    assert!("AA:2".parse::<Range>().is_err());
+    assert!("A2s-K5s".parse::<Range>().is_err());
+    assert!("A2s-A5o".parse::<Range>().is_err());
+    assert!("22-A5s".parse::<Range>().is_err());
+  }
+  #[test]
+  fn test_remove_blocked() {
+    let aces = range("AA");
+    let dead: Vec<Card> = vec!["AH".parse().unwrap()];
+    assert_eq!(aces.without(&dead).len(), 3);
+    let mut kings = range("KK, AKs");
+    kings.remove_blocked(&["KS".parse().unwrap(), "2C".parse().unwrap()]);
+    assert_eq!(kings.len(), 6);
+  }
+  #[test]
+  fn test_class_index() {
+    for (i, class) in HandClass::all().iter().enumerate() {
+      assert_eq!(class.index(), i);
+      assert_eq!(HandClass::from_index(i), Some(*class));
+    }
+    assert_eq!(HandClass::from_index(169), None);
+  }
+  #[test]
+  fn test_display() {
+    let round_trip = |s: &str| range(s).to_string();
+    assert_eq!(round_trip("TT+"), "TT+");
+    assert_eq!(round_trip("22-77"), "22-77");
+    assert_eq!(round_trip("A5s-A2s, KQo"), "A2s-A5s, KQo");
+    assert_eq!(round_trip("ATs+, KK"), "KK, ATs+");
+    assert_eq!(round_trip("AK"), "AKs, AKo");
+    assert_eq!(round_trip("AA:0.5, KK, QQ"), "AA:0.5, QQ-KK");
+    assert_eq!(round_trip("AhKh, QQ"), "QQ, AHKH");
+    assert_eq!(
+      round_trip("QQ+, AsAh:0.25"),
+      "QQ-KK, ADAC, AHAC, AHAD, ASAC, ASAD, ASAH:0.25"
+    );
+  }
+  #[test]
+  fn test_display_round_trip() {
+    let text = "99+, 55-66:0.5, A2s-A5s, AJs+, KTo-KQo, 76s, JhTh:0.75";
+    let parsed = range(text);
+    assert_eq!(range(&parsed.to_string()), parsed);
+  }
+}
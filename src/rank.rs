@@ -1,7 +1,15 @@
+use crate::strings::fill;
 use crate::Face;
 use crate::Hand;
+use crate::Strings;
+use std::fmt;
 
 #[derive(Clone, Debug, Copy, Eq, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize),
+  serde(tag = "type", content = "faces")
+)]
 pub enum Rank {
   HighCard([Face; 5]),
   Pair(Face, [Face; 3]),
@@ -15,6 +23,93 @@ pub enum Rank {
   RoyalFlush,
 }
 
+// The five faces of a straight, high to low. A straight can't be lower
+// than the wheel, so anything up to a Five gives the wheel.
+fn run(high: Face) -> [Face; 5] {
+  let faces = Face::all();
+  match (high as usize).checked_sub(4) {
+    Some(low) => [4, 3, 2, 1, 0].map(|i| faces[low + i]),
+    None => [Face::Five, Face::Four, Face::Three, Face::Two, Face::Ace],
+  }
+}
+
+impl Rank {
+  // the faces of the hand, most significant first
+  pub fn faces(&self) -> [Face; 5] {
+    match *self {
+      Self::HighCard(faces) | Self::Flush(faces) => faces,
+      Self::Pair(a, [b, c, d]) => [a, a, b, c, d],
+      Self::TwoPair(a, b, [c]) => [a, a, b, b, c],
+      Self::ThreeOfAKind(a, [b, c]) => [a, a, a, b, c],
+      Self::Straight(high) | Self::StraightFlush(high) => run(high),
+      Self::FullHouse(a, b) => [a, a, a, b, b],
+      Self::FourOfAKind(a, [b]) => [a, a, a, a, b],
+      Self::RoyalFlush => run(Face::Ace),
+    }
+  }
+  // how many of the five cards make the hand, the rest are kickers
+  pub fn made(&self) -> usize {
+    match self {
+      Self::HighCard(_) => 1,
+      Self::Pair(..) => 2,
+      Self::TwoPair(..) | Self::FourOfAKind(..) => 4,
+      Self::ThreeOfAKind(..) => 3,
+      _ => 5,
+    }
+  }
+  // 0 for a high card up to 9 for a royal flush
+  pub fn category(&self) -> usize {
+    match self {
+      Self::HighCard(_) => 0,
+      Self::Pair(..) => 1,
+      Self::TwoPair(..) => 2,
+      Self::ThreeOfAKind(..) => 3,
+      Self::Straight(_) => 4,
+      Self::Flush(_) => 5,
+      Self::FullHouse(..) => 6,
+      Self::FourOfAKind(..) => 7,
+      Self::StraightFlush(_) => 8,
+      Self::RoyalFlush => 9,
+    }
+  }
+  pub fn describe(&self, strings: &Strings) -> String {
+    let name = |f: Face| strings.faces[f as usize];
+    let plural = |f: Face| strings.plurals[f as usize];
+    let kicker = |f: Face| strings.kickers[f as usize];
+    let low = |high: Face| name(run(high)[4]);
+    match *self {
+      Self::HighCard(faces) => fill(strings.high_card, &[name(faces[0])]),
+      Self::Pair(a, [b, ..]) => fill(strings.pair, &[plural(a), kicker(b)]),
+      Self::TwoPair(a, b, [c]) => fill(strings.two_pair, &[plural(a), plural(b), kicker(c)]),
+      Self::ThreeOfAKind(a, [b, _]) => fill(strings.three_of_a_kind, &[plural(a), kicker(b)]),
+      Self::Straight(Face::Five) => fill(strings.wheel, &[name(Face::Five), low(Face::Five)]),
+      Self::Straight(high) => fill(strings.straight, &[name(high), low(high)]),
+      Self::Flush(faces) => fill(strings.flush, &[name(faces[0])]),
+      Self::FullHouse(a, b) => fill(strings.full_house, &[plural(a), plural(b)]),
+      Self::FourOfAKind(a, [b]) => fill(strings.four_of_a_kind, &[plural(a), kicker(b)]),
+      Self::StraightFlush(Face::Five) => {
+        fill(strings.steel_wheel, &[name(Face::Five), low(Face::Five)])
+      }
+      Self::StraightFlush(high) => fill(strings.straight_flush, &[name(high), low(high)]),
+      Self::RoyalFlush => strings.royal_flush.to_string(),
+    }
+  }
+  // compact form for logs, e.g. "2P KK77J"
+  pub fn abbreviate(&self, strings: &Strings) -> String {
+    let faces: String = self.faces().iter().map(|f| f.to_string()).collect();
+    format!("{} {}", strings.short[self.category()], faces)
+  }
+  pub fn short(&self) -> String {
+    self.abbreviate(&Strings::ENGLISH)
+  }
+}
+
+impl fmt::Display for Rank {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.describe(&Strings::ENGLISH))
+  }
+}
+
 impl From<&Hand> for Rank {
   fn from(hand: &Hand) -> Self {
     match hand.counts() {
@@ -87,6 +182,17 @@ mod tests {
     let hand: Hand = "TH 9C 8D 7S 6H".parse().unwrap();
     let rank: Rank = hand.into();
     assert_eq!(Rank::Straight(Face::Ten), rank);
+    assert_eq!(
+      rank.faces(),
+      [Face::Ten, Face::Nine, Face::Eight, Face::Seven, Face::Six]
+    );
+    // built by hand below the wheel, these read as the wheel
+    let wheel = Rank::Straight(Face::Five).faces();
+    for high in [Face::Two, Face::Three, Face::Four] {
+      assert_eq!(Rank::Straight(high).faces(), wheel);
+      assert_eq!(Rank::StraightFlush(high).faces(), wheel);
+      assert!(!Rank::StraightFlush(high).to_string().is_empty());
+    }
   }
   #[test]
   fn test_threeofakind() {
@@ -122,6 +228,72 @@ mod tests {
     );
   }
   #[test]
+  fn test_display() {
+    let rank = |s: &str| s.parse::<Hand>().unwrap().rank();
+    assert_eq!(
+      rank("KH KD 7D 7S JH").to_string(),
+      "Two Pair, Kings and Sevens with a Jack kicker"
+    );
+    assert_eq!(
+      rank("AH 5C 4D 3S 2H").to_string(),
+      "Straight, Five high (wheel)"
+    );
+    assert_eq!(rank("AH KH QH JH TH").to_string(), "Royal Flush");
+    assert_eq!(
+      rank("8H 8D 8C KS 2H").to_string(),
+      "Three of a Kind, Eights with a King kicker"
+    );
+    assert_eq!(
+      rank("6H 6D KD JS AH").to_string(),
+      "Pair of Sixes with an Ace kicker"
+    );
+    assert_eq!(
+      rank("QH QD QS KS KH").to_string(),
+      "Full House, Queens full of Kings"
+    );
+    assert_eq!(
+      rank("AH 5H 4H 3H 2H").to_string(),
+      "Straight Flush, Five high (steel wheel)"
+    );
+  }
+  #[test]
+  fn test_pokerstars() {
+    let rank = |s: &str| s.parse::<Hand>().unwrap().rank();
+    let describe = |s: &str| rank(s).describe(&Strings::POKERSTARS);
+    assert_eq!(
+      describe("QH QD QS KS KH"),
+      "a full house, Queens full of Kings"
+    );
+    assert_eq!(describe("AH 5C 4D 3S 2H"), "a straight, Ace to Five");
+    assert_eq!(describe("9S TS JS QS KS"), "a straight flush, Nine to King");
+    assert_eq!(describe("2H 2D KD JS 7H"), "a pair of Deuces");
+  }
+  #[test]
+  fn test_short() {
+    let rank = |s: &str| s.parse::<Hand>().unwrap().rank();
+    assert_eq!(rank("KH KD 7D 7S JH").short(), "2P KK77J");
+    assert_eq!(rank("AH 5C 4D 3S 2H").short(), "ST 5432A");
+    assert_eq!(rank("AH AD AC AS 2H").short(), "4K AAAA2");
+    assert_eq!(rank("AH KH QH JH TH").short(), "RF AKQJT");
+  }
+  #[test]
+  fn test_custom_strings() {
+    let french = Strings {
+      faces: [
+        "Deux", "Trois", "Quatre", "Cinq", "Six", "Sept", "Huit", "Neuf", "Dix", "Valet", "Dame",
+        "Roi", "As",
+      ],
+      plurals: [
+        "Deux", "Trois", "Quatre", "Cinq", "Six", "Sept", "Huit", "Neuf", "Dix", "Valets", "Dames",
+        "Rois", "As",
+      ],
+      full_house: "Full aux {0} par les {1}",
+      ..Strings::ENGLISH
+    };
+    let rank = "QH QD QS KS KH".parse::<Hand>().unwrap().rank();
+    assert_eq!(rank.describe(&french), "Full aux Dames par les Rois");
+  }
+  #[test]
   fn test_ord() {
     let hands: Vec<Hand> = vec![
       "AH KS QD 9S 7H",
@@ -0,0 +1,71 @@
+// A small seeded generator (SplitMix64) so simulations can be replayed
+// exactly from a seed.
+#[derive(Clone, Debug, Copy, Eq, PartialEq)]
+pub struct Rng {
+  state: u64,
+}
+
+impl Rng {
+  pub fn new(seed: u64) -> Self {
+    Self { state: seed }
+  }
+  pub fn next_u64(&mut self) -> u64 {
+    self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = self.state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+  }
+  // uniform in 0..n
+  pub fn below(&mut self, n: usize) -> usize {
+    ((self.next_u64() as u128 * n as u128) >> 64) as usize
+  }
+  // uniform in [0, 1)
+  pub fn next_f64(&mut self) -> f64 {
+    (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+  }
+  pub fn shuffle<T>(&mut self, items: &mut [T]) {
+    for i in (1..items.len()).rev() {
+      items.swap(i, self.below(i + 1));
+    }
+  }
+  // Moves a random selection of k items to the front, leaving the rest in
+  // some order behind them.
+  pub fn partial_shuffle<T>(&mut self, items: &mut [T], k: usize) {
+    let n = items.len();
+    for i in 0..k.min(n) {
+      items.swap(i, i + self.below(n - i));
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_seeded() {
+    let mut a = Rng::new(7);
+    let mut b = Rng::new(7);
+    assert_eq!(a.next_u64(), b.next_u64());
+    assert_ne!(a.next_u64(), Rng::new(8).next_u64());
+  }
+  #[test]
+  fn test_below() {
+    let mut rng = Rng::new(1);
+    let mut seen = [0; 6];
+    for _ in 0..6000 {
+      seen[rng.below(6)] += 1;
+    }
+    assert!(seen.iter().all(|&n| n > 850 && n < 1150), "{:?}", seen);
+  }
+  #[test]
+  fn test_shuffle() {
+    let mut rng = Rng::new(3);
+    let mut items: Vec<u32> = (0..52).collect();
+    rng.shuffle(&mut items);
+    assert_ne!(items, (0..52).collect::<Vec<u32>>());
+    items.sort();
+    assert_eq!(items, (0..52).collect::<Vec<u32>>());
+  }
+}
@@ -0,0 +1,269 @@
+// Serde support, behind the `serde` feature. Cards, faces and suits are
+// their usual strings ("AH", "A", "H"), a hand is a list of five cards, a
+// deck a list of nine and a deal is its hole cards and the community cards
+// dealt so far:
+//   {"hole": ["AS", "3S"], "community": ["9S", "TS", "JS"]}
+// A rank is tagged by its category:
+//   {"type": "Pair", "faces": ["K", ["Q", "J", "9"]]}
+// Everything read back is checked, so it could have come from real cards.
+
+use crate::Card;
+use crate::Deal;
+use crate::Deck;
+use crate::Face;
+use crate::Hand;
+use crate::Rank;
+use crate::Suit;
+use serde::de;
+use serde::Deserialize;
+use serde::Deserializer;
+use serde::Serialize;
+use serde::Serializer;
+use std::fmt::Display;
+use std::str::FromStr;
+
+fn to_string<T: Display, S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+  serializer.collect_str(value)
+}
+
+fn from_string<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+  T: FromStr<Err = &'static str>,
+  D: Deserializer<'de>,
+{
+  let s = String::deserialize(deserializer)?;
+  s.parse().map_err(de::Error::custom)
+}
+
+macro_rules! as_string {
+  ($($t:ty),*) => {$(
+    impl Serialize for $t {
+      fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        to_string(self, serializer)
+      }
+    }
+    impl<'de> Deserialize<'de> for $t {
+      fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        from_string(deserializer)
+      }
+    }
+  )*};
+}
+
+as_string!(Card, Face, Suit);
+
+impl Serialize for Hand {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    self.cards().serialize(serializer)
+  }
+}
+
+impl<'de> Deserialize<'de> for Hand {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let cards = Vec::<Card>::deserialize(deserializer)?;
+    if cards.len() != 5 {
+      return Err(de::Error::custom("A hand has five cards."));
+    }
+    if repeats(&cards) {
+      return Err(de::Error::custom("Hand has a repeated card."));
+    }
+    Ok(Hand::new(&cards))
+  }
+}
+
+fn repeats(cards: &[Card]) -> bool {
+  (1..cards.len()).any(|i| cards[..i].contains(&cards[i]))
+}
+
+#[derive(Serialize, Deserialize)]
+struct DealFields {
+  hole: [Card; 2],
+  community: Vec<Card>,
+}
+
+impl Serialize for Deal {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    DealFields {
+      hole: self.hole(),
+      community: self.community().to_vec(),
+    }
+    .serialize(serializer)
+  }
+}
+
+impl<'de> Deserialize<'de> for Deal {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let fields = DealFields::deserialize(deserializer)?;
+    Deal::partial(fields.hole, &fields.community).map_err(de::Error::custom)
+  }
+}
+
+impl Serialize for Deck {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    self.cards().serialize(serializer)
+  }
+}
+
+impl<'de> Deserialize<'de> for Deck {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let cards = <[Card; 9]>::deserialize(deserializer)?;
+    Deck::from_cards(cards).map_err(de::Error::custom)
+  }
+}
+
+// Rank as it's written, before it's checked
+#[derive(Deserialize)]
+#[serde(tag = "type", content = "faces")]
+enum RankFields {
+  HighCard([Face; 5]),
+  Pair(Face, [Face; 3]),
+  TwoPair(Face, Face, [Face; 1]),
+  ThreeOfAKind(Face, [Face; 2]),
+  Straight(Face),
+  Flush([Face; 5]),
+  FullHouse(Face, Face),
+  FourOfAKind(Face, [Face; 1]),
+  StraightFlush(Face),
+  RoyalFlush,
+}
+
+impl From<RankFields> for Rank {
+  fn from(fields: RankFields) -> Self {
+    match fields {
+      RankFields::HighCard(faces) => Rank::HighCard(faces),
+      RankFields::Pair(a, kickers) => Rank::Pair(a, kickers),
+      RankFields::TwoPair(a, b, kicker) => Rank::TwoPair(a, b, kicker),
+      RankFields::ThreeOfAKind(a, kickers) => Rank::ThreeOfAKind(a, kickers),
+      RankFields::Straight(high) => Rank::Straight(high),
+      RankFields::Flush(faces) => Rank::Flush(faces),
+      RankFields::FullHouse(a, b) => Rank::FullHouse(a, b),
+      RankFields::FourOfAKind(a, kicker) => Rank::FourOfAKind(a, kicker),
+      RankFields::StraightFlush(high) => Rank::StraightFlush(high),
+      RankFields::RoyalFlush => Rank::RoyalFlush,
+    }
+  }
+}
+
+// A rank is real if five cards with its faces rank the same: suited for
+// flushes, otherwise spread over the suits so there's no flush.
+fn is_real(rank: &Rank) -> bool {
+  let suited = matches!(
+    rank,
+    Rank::Flush(_) | Rank::StraightFlush(_) | Rank::RoyalFlush
+  );
+  let suits = Suit::all();
+  let mut cards = [Card::new(Face::Ace, Suit::Spades); 5];
+  for (i, &face) in rank.faces().iter().enumerate() {
+    let suit = if suited { Suit::Spades } else { suits[i % 4] };
+    cards[i] = Card::new(face, suit);
+  }
+  !repeats(&cards) && Hand::new(&cards).rank() == *rank
+}
+
+impl<'de> Deserialize<'de> for Rank {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let rank = Rank::from(RankFields::deserialize(deserializer)?);
+    if !is_real(&rank) {
+      return Err(de::Error::custom("No hand has this rank."));
+    }
+    Ok(rank)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::*;
+  use serde_json::json;
+
+  fn round_trip<T>(value: &T) -> T
+  where
+    T: serde::Serialize + serde::de::DeserializeOwned,
+  {
+    serde_json::from_str(&serde_json::to_string(value).unwrap()).unwrap()
+  }
+
+  #[test]
+  fn test_cards() {
+    let card: Card = "TH".parse().unwrap();
+    assert_eq!(serde_json::to_value(card).unwrap(), json!("TH"));
+    assert_eq!(round_trip(&card), card);
+    assert_eq!(serde_json::to_value(Face::Ace).unwrap(), json!("A"));
+    assert_eq!(serde_json::to_value(Suit::Clubs).unwrap(), json!("C"));
+    assert_eq!(serde_json::from_value::<Card>(json!("Th")).unwrap(), card);
+    assert!(serde_json::from_value::<Card>(json!("1H")).is_err());
+    assert!(serde_json::from_value::<Suit>(json!(3)).is_err());
+  }
+  #[test]
+  fn test_hand_and_rank() {
+    let hand: Hand = "KH KS QD JC 9C".parse().unwrap();
+    let value = serde_json::to_value(hand).unwrap();
+    assert_eq!(value, json!(["KS", "KH", "QD", "JC", "9C"]));
+    assert_eq!(round_trip(&hand), hand);
+    assert!(serde_json::from_value::<Hand>(json!(["KS", "KH"])).is_err());
+    assert!(serde_json::from_value::<Hand>(json!(["KS", "KS", "QD", "JC", "9C"])).is_err());
+
+    let rank = hand.rank();
+    assert_eq!(
+      serde_json::to_value(rank).unwrap(),
+      json!({"type": "Pair", "faces": ["K", ["Q", "J", "9"]]})
+    );
+    assert_eq!(round_trip(&rank), rank);
+    assert_eq!(
+      serde_json::to_value(Rank::RoyalFlush).unwrap(),
+      json!({"type": "RoyalFlush"})
+    );
+    assert_eq!(
+      round_trip(&Rank::Straight(Face::Five)),
+      Rank::Straight(Face::Five)
+    );
+  }
+  #[test]
+  fn test_real_ranks() {
+    // every rank a hand makes reads back
+    let mut rng = Rng::new(46);
+    let mut deck = Card::all();
+    for _ in 0..2000 {
+      rng.partial_shuffle(&mut deck, 5);
+      let rank = Hand::new(&deck[..5]).rank();
+      assert_eq!(round_trip(&rank), rank);
+    }
+    let fake = [
+      json!({"type": "Straight", "faces": "3"}),
+      json!({"type": "StraightFlush", "faces": "A"}),
+      json!({"type": "Pair", "faces": ["K", ["9", "Q", "J"]]}),
+      json!({"type": "Pair", "faces": ["K", ["K", "Q", "J"]]}),
+      json!({"type": "TwoPair", "faces": ["7", "A", ["K"]]}),
+      json!({"type": "FullHouse", "faces": ["Q", "Q"]}),
+      json!({"type": "HighCard", "faces": ["9", "8", "7", "6", "5"]}),
+      json!({"type": "Flush", "faces": ["A", "A", "7", "6", "5"]}),
+    ];
+    for value in fake {
+      assert!(
+        serde_json::from_value::<Rank>(value.clone()).is_err(),
+        "{}",
+        value
+      );
+    }
+  }
+  #[test]
+  fn test_deal_and_deck() {
+    let deck = Deck::new([40, 41, 42, 43, 48, 49, 50, 51, 52]);
+    assert_eq!(
+      serde_json::to_value(deck).unwrap(),
+      json!(["AS", "2S", "3S", "4S", "9S", "TS", "JS", "QS", "KS"])
+    );
+    assert_eq!(round_trip(&deck), deck);
+    let repeated = json!(["AS", "AS", "3S", "4S", "9S", "TS", "JS", "QS", "KS"]);
+    assert!(serde_json::from_value::<Deck>(repeated).is_err());
+    assert!(serde_json::from_value::<Deck>(json!(["AS", "2S"])).is_err());
+    let [deal, _] = deck.deal();
+    assert_eq!(round_trip(&deal), deal);
+
+    let flop = json!({"hole": ["AS", "3S"], "community": ["9S", "TS", "JS"]});
+    let deal: Deal = serde_json::from_value(flop.clone()).unwrap();
+    assert_eq!(deal.street(), Street::Flop);
+    assert_eq!(serde_json::to_value(deal).unwrap(), flop);
+    let repeated = json!({"hole": ["AS", "AS"], "community": ["9S", "TS", "JS"]});
+    assert!(serde_json::from_value::<Deal>(repeated).is_err());
+  }
+}
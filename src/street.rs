@@ -0,0 +1,59 @@
+use std::fmt;
+
+#[derive(Clone, Debug, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Street {
+  Preflop,
+  Flop,
+  Turn,
+  River,
+}
+
+impl Street {
+  pub fn all() -> [Self; 4] {
+    [Self::Preflop, Self::Flop, Self::Turn, Self::River]
+  }
+  // number of community cards showing once the street is dealt
+  pub fn board_len(&self) -> usize {
+    match self {
+      Self::Preflop => 0,
+      Self::Flop => 3,
+      Self::Turn => 4,
+      Self::River => 5,
+    }
+  }
+  pub fn next(&self) -> Option<Self> {
+    match self {
+      Self::Preflop => Some(Self::Flop),
+      Self::Flop => Some(Self::Turn),
+      Self::Turn => Some(Self::River),
+      Self::River => None,
+    }
+  }
+}
+
+impl fmt::Display for Street {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "{}",
+      match self {
+        Self::Preflop => "Preflop",
+        Self::Flop => "Flop",
+        Self::Turn => "Turn",
+        Self::River => "River",
+      }
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_next() {
+    assert_eq!(Street::Preflop.next(), Some(Street::Flop));
+    assert_eq!(Street::River.next(), None);
+    assert_eq!(Street::Turn.board_len(), 4);
+  }
+}
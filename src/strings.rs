@@ -0,0 +1,136 @@
+// Words used to describe a Rank. Faces are listed Two to Ace.
+//
+// Templates take positional arguments, {0} to {2}:
+//   high_card, flush          {0} the high card
+//   pair                      {0} the pair (plural), {1} the top kicker
+//   two_pair                  {0} the top pair, {1} the bottom pair (plural),
+//                             {2} the kicker
+//   three_of_a_kind           {0} the trips (plural), {1} the top kicker
+//   straight, wheel,
+//   straight_flush,
+//   steel_wheel               {0} the high card, {1} the low card
+//   full_house                {0} the trips, {1} the pair (plural)
+//   four_of_a_kind            {0} the quads (plural), {1} the kicker
+// Kickers use the kickers list, which carries any article ("a Jack").
+#[derive(Clone, Debug, Copy, Eq, PartialEq)]
+pub struct Strings<'a> {
+  pub faces: [&'a str; 13],
+  pub plurals: [&'a str; 13],
+  pub kickers: [&'a str; 13],
+  pub high_card: &'a str,
+  pub pair: &'a str,
+  pub two_pair: &'a str,
+  pub three_of_a_kind: &'a str,
+  pub straight: &'a str,
+  pub wheel: &'a str,
+  pub flush: &'a str,
+  pub full_house: &'a str,
+  pub four_of_a_kind: &'a str,
+  pub straight_flush: &'a str,
+  pub steel_wheel: &'a str,
+  pub royal_flush: &'a str,
+  // abbreviations for each category, high card to royal flush
+  pub short: [&'a str; 10],
+}
+
+impl Strings<'static> {
+  pub const ENGLISH: Self = Self {
+    faces: [
+      "Two", "Three", "Four", "Five", "Six", "Seven", "Eight", "Nine", "Ten", "Jack", "Queen",
+      "King", "Ace",
+    ],
+    plurals: [
+      "Twos", "Threes", "Fours", "Fives", "Sixes", "Sevens", "Eights", "Nines", "Tens", "Jacks",
+      "Queens", "Kings", "Aces",
+    ],
+    kickers: [
+      "a Two", "a Three", "a Four", "a Five", "a Six", "a Seven", "an Eight", "a Nine", "a Ten",
+      "a Jack", "a Queen", "a King", "an Ace",
+    ],
+    high_card: "High Card, {0} high",
+    pair: "Pair of {0} with {1} kicker",
+    two_pair: "Two Pair, {0} and {1} with {2} kicker",
+    three_of_a_kind: "Three of a Kind, {0} with {1} kicker",
+    straight: "Straight, {0} high",
+    wheel: "Straight, {0} high (wheel)",
+    flush: "Flush, {0} high",
+    full_house: "Full House, {0} full of {1}",
+    four_of_a_kind: "Four of a Kind, {0} with {1} kicker",
+    straight_flush: "Straight Flush, {0} high",
+    steel_wheel: "Straight Flush, {0} high (steel wheel)",
+    royal_flush: "Royal Flush",
+    short: ["HC", "1P", "2P", "3K", "ST", "FL", "FH", "4K", "SF", "RF"],
+  };
+
+  // the wording PokerStars uses in hand histories
+  pub const POKERSTARS: Self = Self {
+    faces: [
+      "Deuce", "Three", "Four", "Five", "Six", "Seven", "Eight", "Nine", "Ten", "Jack", "Queen",
+      "King", "Ace",
+    ],
+    plurals: [
+      "Deuces", "Threes", "Fours", "Fives", "Sixes", "Sevens", "Eights", "Nines", "Tens", "Jacks",
+      "Queens", "Kings", "Aces",
+    ],
+    kickers: [
+      "Deuce", "Three", "Four", "Five", "Six", "Seven", "Eight", "Nine", "Ten", "Jack", "Queen",
+      "King", "Ace",
+    ],
+    high_card: "high card {0}",
+    pair: "a pair of {0}",
+    two_pair: "two pair, {0} and {1}",
+    three_of_a_kind: "three of a kind, {0}",
+    straight: "a straight, {1} to {0}",
+    wheel: "a straight, {1} to {0}",
+    flush: "a flush, {0} high",
+    full_house: "a full house, {0} full of {1}",
+    four_of_a_kind: "four of a kind, {0}",
+    straight_flush: "a straight flush, {1} to {0}",
+    steel_wheel: "a straight flush, {1} to {0}",
+    royal_flush: "a Royal Flush",
+    short: ["HC", "1P", "2P", "3K", "ST", "FL", "FH", "4K", "SF", "RF"],
+  };
+}
+
+// Substitutes {0}, {1}, ... in a template, in one pass so braces in the
+// arguments are left alone.
+pub fn fill(template: &str, args: &[&str]) -> String {
+  let mut filled = String::with_capacity(template.len());
+  let mut rest = template;
+  while let Some(open) = rest.find('{') {
+    filled.push_str(&rest[..open]);
+    let after = &rest[open + 1..];
+    let arg = after.find('}').and_then(|close| {
+      let i: usize = after[..close].parse().ok()?;
+      Some((args.get(i)?, close))
+    });
+    match arg {
+      Some((arg, close)) => {
+        filled.push_str(arg);
+        rest = &after[close + 1..];
+      }
+      None => {
+        filled.push('{');
+        rest = after;
+      }
+    }
+  }
+  filled.push_str(rest);
+  filled
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_fill() {
+    assert_eq!(
+      fill("{1} full of {0}", &["Kings", "Queens"]),
+      "Queens full of Kings"
+    );
+    assert_eq!(fill("Royal Flush", &[]), "Royal Flush");
+    assert_eq!(fill("{0} and {1}", &["{1}", "x"]), "{1} and x");
+    assert_eq!(fill("{0} {2} {", &["a"]), "a {2} {");
+  }
+}
@@ -1,7 +1,7 @@
 use std::fmt;
 use std::str::FromStr;
 
-#[derive(Clone, Debug, Copy, Eq, PartialEq, Ord, PartialOrd)]
+#[derive(Clone, Debug, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub enum Suit {
   Clubs,
   Diamonds,
@@ -10,6 +10,9 @@ pub enum Suit {
 }
 
 impl Suit {
+  pub fn all() -> [Self; 4] {
+    [Self::Clubs, Self::Diamonds, Self::Hearts, Self::Spades]
+  }
   pub fn counters() -> [(u8, Self); 4] {
     [
       (0, Self::Clubs),
@@ -52,10 +55,10 @@ impl FromStr for Suit {
 
   fn from_str(s: &str) -> Result<Self, Self::Err> {
     match s {
-      "C" => Ok(Self::Clubs),
-      "D" => Ok(Self::Diamonds),
-      "H" => Ok(Self::Hearts),
-      "S" => Ok(Self::Spades),
+      "C" | "c" => Ok(Self::Clubs),
+      "D" | "d" => Ok(Self::Diamonds),
+      "H" | "h" => Ok(Self::Hearts),
+      "S" | "s" => Ok(Self::Spades),
       _ => Err("Invalid suit."),
     }
   }
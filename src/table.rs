@@ -0,0 +1,404 @@
+use crate::Action;
+use crate::Rank;
+use crate::Street;
+
+// What the player due to act may do. Raise sizes are the player's total for
+// the street, so for an opening bet they are the bet itself.
+#[derive(Clone, Debug, Copy, Eq, PartialEq)]
+pub struct Legal {
+  pub current_bet: u64,
+  pub to_call: u64,
+  pub min_raise: Option<u64>,
+  pub max_raise: Option<u64>,
+}
+
+impl Legal {
+  pub fn can_check(&self) -> bool {
+    self.to_call == 0
+  }
+  // a bet or raise to the given total, clamped to the legal sizes
+  pub fn raise(&self, to: u64) -> Option<Action> {
+    let to = to.clamp(self.min_raise?, self.max_raise?);
+    if self.current_bet == 0 {
+      Some(Action::Bet(to))
+    } else {
+      Some(Action::Raise(to))
+    }
+  }
+  pub fn call(&self) -> Action {
+    if self.can_check() {
+      Action::Check
+    } else {
+      Action::Call(self.to_call)
+    }
+  }
+  // fold, check or call, and the smallest and largest raise
+  pub fn actions(&self) -> Vec<Action> {
+    let mut actions = Vec::new();
+    if !self.can_check() {
+      actions.push(Action::Fold);
+    }
+    actions.push(self.call());
+    if let (Some(min), Some(max)) = (self.min_raise, self.max_raise) {
+      actions.push(self.raise(min).unwrap());
+      if max > min {
+        actions.push(self.raise(max).unwrap());
+      }
+    }
+    actions
+  }
+}
+
+// The betting for one hand of no limit hold'em. Cards are left to the caller:
+// the table only says which street is being played.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Table {
+  button: usize,
+  big_blind: u64,
+  street: Street,
+  stacks: Vec<u64>,
+  street_in: Vec<u64>,
+  total_in: Vec<u64>,
+  folded: Vec<bool>,
+  acted: Vec<bool>,
+  current_bet: u64,
+  min_raise: u64,
+  to_act: Option<usize>,
+  forced_bets: Vec<(usize, u64)>,
+}
+
+impl Table {
+  pub fn new(
+    stacks: Vec<u64>,
+    button: usize,
+    small_blind: u64,
+    big_blind: u64,
+    ante: u64,
+  ) -> Result<Self, &'static str> {
+    let n = stacks.len();
+    if n < 2 {
+      return Err("A hand needs at least two players.");
+    }
+    if button >= n {
+      return Err("Button is not at a seat.");
+    }
+    let mut table = Self {
+      button,
+      big_blind,
+      street: Street::Preflop,
+      stacks,
+      street_in: vec![0; n],
+      total_in: vec![0; n],
+      folded: vec![false; n],
+      acted: vec![false; n],
+      current_bet: 0,
+      min_raise: big_blind,
+      to_act: None,
+      forced_bets: Vec::new(),
+    };
+    if ante > 0 {
+      for seat in 0..n {
+        let amount = ante.min(table.stacks[seat]);
+        table.stacks[seat] -= amount;
+        table.total_in[seat] += amount;
+        table.forced_bets.push((seat, amount));
+      }
+    }
+    // heads up the button posts the small blind
+    let small = if n == 2 { button } else { (button + 1) % n };
+    let big = (small + 1) % n;
+    for (seat, blind) in [(small, small_blind), (big, big_blind)] {
+      let amount = blind.min(table.stacks[seat]);
+      table.put(seat, amount);
+      table.forced_bets.push((seat, amount));
+    }
+    table.current_bet = big_blind;
+    table.to_act = table.next_to_act(big);
+    Ok(table)
+  }
+  pub fn button(&self) -> usize {
+    self.button
+  }
+  pub fn big_blind(&self) -> u64 {
+    self.big_blind
+  }
+  pub fn street(&self) -> Street {
+    self.street
+  }
+  pub fn stacks(&self) -> &[u64] {
+    &self.stacks
+  }
+  // chips each player has put in the pot over the whole hand
+  pub fn contributions(&self) -> &[u64] {
+    &self.total_in
+  }
+  pub fn folded(&self) -> &[bool] {
+    &self.folded
+  }
+  pub fn forced_bets(&self) -> &[(usize, u64)] {
+    &self.forced_bets
+  }
+  pub fn pot(&self) -> u64 {
+    self.total_in.iter().sum()
+  }
+  pub fn to_act(&self) -> Option<usize> {
+    self.to_act
+  }
+  pub fn is_finished(&self) -> bool {
+    self.to_act.is_none()
+  }
+  pub fn live(&self) -> Vec<usize> {
+    (0..self.stacks.len())
+      .filter(|&s| !self.folded[s])
+      .collect()
+  }
+  pub fn is_showdown(&self) -> bool {
+    self.is_finished() && self.live().len() > 1
+  }
+  pub fn legal(&self) -> Option<Legal> {
+    let seat = self.to_act?;
+    let stack = self.stacks[seat];
+    let to_call = (self.current_bet - self.street_in[seat]).min(stack);
+    let all_in = self.street_in[seat] + stack;
+    let opponents =
+      (0..self.stacks.len()).any(|s| s != seat && !self.folded[s] && self.stacks[s] > 0);
+    let (min_raise, max_raise) = if !self.acted[seat] && all_in > self.current_bet && opponents {
+      let min = (self.current_bet + self.min_raise).min(all_in);
+      (Some(min), Some(all_in))
+    } else {
+      (None, None)
+    };
+    Some(Legal {
+      current_bet: self.current_bet,
+      to_call,
+      min_raise,
+      max_raise,
+    })
+  }
+  pub fn apply(&mut self, action: Action) -> Result<(), &'static str> {
+    let seat = self.to_act.ok_or("Nobody is due to act.")?;
+    let legal = self.legal().unwrap();
+    let raise_to = |to: u64| match (legal.min_raise, legal.max_raise) {
+      (Some(min), Some(max)) if to >= min && to <= max => Ok(to),
+      (Some(_), Some(_)) => Err("Raise size is not allowed."),
+      _ => Err("Raising is not allowed."),
+    };
+    match action {
+      Action::Fold => self.folded[seat] = true,
+      Action::Check if legal.can_check() => (),
+      Action::Check => return Err("Cannot check facing a bet."),
+      Action::Call(_) if legal.can_check() => return Err("Nothing to call."),
+      Action::Call(amount) if amount != legal.to_call => {
+        return Err("Call amount does not match the bet.")
+      }
+      Action::Call(amount) => self.put(seat, amount),
+      Action::Bet(_) if legal.current_bet > 0 => return Err("Cannot bet facing a bet."),
+      Action::Raise(_) if legal.current_bet == 0 => return Err("Nothing to raise."),
+      Action::Bet(to) | Action::Raise(to) => {
+        let to = raise_to(to)?;
+        self.put(seat, to - self.street_in[seat]);
+        if to - self.current_bet >= self.min_raise {
+          // a full raise lets everyone act again
+          self.min_raise = to - self.current_bet;
+          self.acted.iter_mut().for_each(|a| *a = false);
+        }
+        self.current_bet = to;
+      }
+    }
+    self.acted[seat] = true;
+    self.to_act = self.next_to_act(seat);
+    Ok(())
+  }
+  // Splits the pot, side pots included, between the best hands. Players
+  // without a rank (folded or mucked) cannot win unless nobody else is left.
+  pub fn payouts(&self, ranks: &[Option<Rank>]) -> Vec<u64> {
+    let n = self.stacks.len();
+    let mut payouts = vec![0; n];
+    let live = self.live();
+    let mut levels: Vec<u64> = live.iter().map(|&s| self.total_in[s]).collect();
+    levels.sort();
+    levels.dedup();
+    let mut previous = 0;
+    for (i, &level) in levels.iter().enumerate() {
+      let top = if i + 1 == levels.len() {
+        u64::MAX
+      } else {
+        level
+      };
+      let pot: u64 = self
+        .total_in
+        .iter()
+        .map(|&t| t.min(top) - t.min(previous))
+        .sum();
+      previous = level;
+      let eligible: Vec<usize> = live
+        .iter()
+        .copied()
+        .filter(|&s| self.total_in[s] >= level)
+        .collect();
+      let best = eligible
+        .iter()
+        .map(|&s| ranks.get(s).copied().flatten())
+        .max()
+        .flatten();
+      // odd chips go to the first winners left of the button
+      let winners: Vec<usize> = (1..=n)
+        .map(|i| (self.button + i) % n)
+        .filter(|s| eligible.contains(s))
+        .filter(|&s| best.is_none() || ranks.get(s).copied().flatten() == best)
+        .collect();
+      let share = pot / winners.len() as u64;
+      let odd = (pot % winners.len() as u64) as usize;
+      for (i, &winner) in winners.iter().enumerate() {
+        payouts[winner] += share + if i < odd { 1 } else { 0 };
+      }
+    }
+    payouts
+  }
+  fn put(&mut self, seat: usize, amount: u64) {
+    self.stacks[seat] -= amount;
+    self.street_in[seat] += amount;
+    self.total_in[seat] += amount;
+  }
+  fn needs_action(&self, seat: usize) -> bool {
+    if self.folded[seat] || self.stacks[seat] == 0 {
+      return false;
+    }
+    if self.street_in[seat] < self.current_bet {
+      return true;
+    }
+    let opponents =
+      (0..self.stacks.len()).any(|s| s != seat && !self.folded[s] && self.stacks[s] > 0);
+    !self.acted[seat] && opponents
+  }
+  fn next_to_act(&mut self, from: usize) -> Option<usize> {
+    let n = self.stacks.len();
+    if self.live().len() > 1 {
+      if let Some(seat) = (1..=n)
+        .map(|i| (from + i) % n)
+        .find(|&s| self.needs_action(s))
+      {
+        return Some(seat);
+      }
+    }
+    self.end_round()
+  }
+  fn end_round(&mut self) -> Option<usize> {
+    // return whatever part of the largest bet nobody matched
+    let mut order: Vec<usize> = (0..self.stacks.len()).collect();
+    order.sort_by_key(|&s| std::cmp::Reverse(self.street_in[s]));
+    let excess = self.street_in[order[0]] - self.street_in[order[1]];
+    self.stacks[order[0]] += excess;
+    self.total_in[order[0]] -= excess;
+    self.street_in.iter_mut().for_each(|s| *s = 0);
+    self.acted.iter_mut().for_each(|a| *a = false);
+    self.current_bet = 0;
+    self.min_raise = self.big_blind;
+
+    let live = self.live();
+    if live.len() <= 1 {
+      return None;
+    }
+    if live.iter().filter(|&&s| self.stacks[s] > 0).count() <= 1 {
+      // nobody left to bet against, deal out the board
+      self.street = Street::River;
+      return None;
+    }
+    self.street = self.street.next()?;
+    // postflop the first player left of the button acts first
+    self.next_to_act(self.button)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::Hand;
+
+  fn rank(hand: &str) -> Option<Rank> {
+    Some(hand.parse::<Hand>().unwrap().rank())
+  }
+
+  #[test]
+  fn test_blinds() {
+    let table = Table::new(vec![1000; 3], 0, 10, 20, 0).unwrap();
+    assert_eq!(table.forced_bets(), &[(1, 10), (2, 20)]);
+    assert_eq!(table.to_act(), Some(0));
+    let heads_up = Table::new(vec![1000; 2], 1, 10, 20, 5).unwrap();
+    assert_eq!(heads_up.forced_bets(), &[(0, 5), (1, 5), (1, 10), (0, 20)]);
+    assert_eq!(heads_up.to_act(), Some(1));
+    assert_eq!(heads_up.pot(), 40);
+    assert_eq!(
+      Table::new(vec![1000], 0, 10, 20, 0).unwrap_err(),
+      "A hand needs at least two players."
+    );
+    assert!(Table::new(vec![1000; 2], 2, 10, 20, 0).is_err());
+  }
+  #[test]
+  fn test_betting_round() {
+    let mut table = Table::new(vec![1000; 3], 0, 10, 20, 0).unwrap();
+    assert_eq!(
+      table.legal().unwrap().actions(),
+      vec![
+        Action::Fold,
+        Action::Call(20),
+        Action::Raise(40),
+        Action::Raise(1000)
+      ]
+    );
+    assert!(table.apply(Action::Check).is_err());
+    assert!(table.apply(Action::Raise(30)).is_err());
+    table.apply(Action::Raise(60)).unwrap();
+    table.apply(Action::Fold).unwrap();
+    table.apply(Action::Call(40)).unwrap();
+    assert_eq!(table.street(), Street::Flop);
+    assert_eq!(table.to_act(), Some(2));
+    assert!(table.apply(Action::Raise(40)).is_err());
+    table.apply(Action::Check).unwrap();
+    table.apply(Action::Bet(100)).unwrap();
+    table.apply(Action::Fold).unwrap();
+    assert!(table.is_finished());
+    assert!(!table.is_showdown());
+    assert_eq!(table.contributions(), &[60, 10, 60]);
+    assert_eq!(table.payouts(&[None, None, None]), vec![130, 0, 0]);
+  }
+  #[test]
+  fn test_big_blind_option() {
+    let mut table = Table::new(vec![1000; 3], 0, 10, 20, 0).unwrap();
+    table.apply(Action::Call(20)).unwrap();
+    table.apply(Action::Call(10)).unwrap();
+    assert_eq!(table.to_act(), Some(2));
+    assert!(table.legal().unwrap().can_check());
+    table.apply(Action::Check).unwrap();
+    assert_eq!(table.street(), Street::Flop);
+  }
+  #[test]
+  fn test_short_all_in_does_not_reopen() {
+    let mut table = Table::new(vec![1000, 1000, 130], 0, 10, 20, 0).unwrap();
+    table.apply(Action::Raise(100)).unwrap();
+    table.apply(Action::Fold).unwrap();
+    table.apply(Action::Raise(130)).unwrap();
+    let legal = table.legal().unwrap();
+    assert_eq!(legal.to_call, 30);
+    assert_eq!(legal.min_raise, None);
+    table.apply(Action::Call(30)).unwrap();
+    assert!(table.is_showdown());
+    assert_eq!(table.street(), Street::River);
+  }
+  #[test]
+  fn test_side_pots() {
+    let mut table = Table::new(vec![1000, 300, 1000], 0, 10, 20, 0).unwrap();
+    table.apply(Action::Raise(1000)).unwrap();
+    table.apply(Action::Call(290)).unwrap();
+    table.apply(Action::Call(980)).unwrap();
+    assert!(table.is_showdown());
+    let ranks = [
+      rank("AH KH QH JH 9H"),
+      rank("AS AD AC KS KH"),
+      rank("2H 2D KD JS 7H"),
+    ];
+    assert_eq!(table.payouts(&ranks), vec![1400, 900, 0]);
+    let split = [rank("2H 2D KD JS 7H"), None, rank("2S 2C KS JD 7C")];
+    assert_eq!(table.payouts(&split), vec![1150, 0, 1150]);
+  }
+}
@@ -0,0 +1,268 @@
+// Features of a flop, turn or river that don't depend on anyone's hand.
+
+use crate::Card;
+use crate::Combo;
+use crate::Deal;
+use crate::Face;
+use crate::Rank;
+use crate::Suit;
+
+// How many cards share a suit. Monotone means a flush is already possible,
+// so a turn with three hearts counts as monotone.
+#[derive(Clone, Debug, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Suits {
+  Rainbow,
+  TwoTone,
+  Monotone,
+}
+
+#[derive(Clone, Debug, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Pairing {
+  Unpaired,
+  Paired,
+  TwoPair,
+  Trips,
+  FullHouse,
+  Quads,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Texture {
+  pub suits: Suits,
+  // the suit a flush would be in, if one is possible
+  pub flush_suit: Option<Suit>,
+  pub pairing: Pairing,
+  // high cards of the straights two hole cards could make, best first
+  pub straights: Vec<Face>,
+}
+
+fn validate(board: &[Card]) -> Result<(), &'static str> {
+  if !(3..=5).contains(&board.len()) {
+    return Err("A board has three to five cards.");
+  }
+  let mut sorted = board.to_vec();
+  sorted.sort();
+  sorted.dedup();
+  if sorted.len() != board.len() {
+    return Err("Board has a repeated card.");
+  }
+  Ok(())
+}
+
+impl Texture {
+  pub fn new(board: &[Card]) -> Result<Self, &'static str> {
+    validate(board)?;
+    let mut suits = [0; 4];
+    let mut faces = [0; 13];
+    for card in board {
+      suits[card.suit() as usize] += 1;
+      faces[card.face() as usize] += 1;
+    }
+
+    let most = suits.iter().max().copied().unwrap();
+    let flush_suit = Suit::all().into_iter().find(|&s| suits[s as usize] >= 3);
+    let suits = match most {
+      1 => Suits::Rainbow,
+      2 => Suits::TwoTone,
+      _ => Suits::Monotone,
+    };
+
+    let mut groups: Vec<u8> = faces.iter().copied().filter(|&n| n > 1).collect();
+    groups.sort();
+    groups.reverse();
+    let pairing = match groups[..] {
+      [] => Pairing::Unpaired,
+      [4, ..] => Pairing::Quads,
+      [3, 2] => Pairing::FullHouse,
+      [3, ..] => Pairing::Trips,
+      [2, 2] => Pairing::TwoPair,
+      _ => Pairing::Paired,
+    };
+
+    // a straight needs three of its faces on the board
+    let all = Face::all();
+    let on_board = |face: Face| faces[face as usize] > 0;
+    let mut straights = Vec::new();
+    for high in (3..13).rev() {
+      let run = (0..5).map(|i| all[(high + 13 - i) % 13]);
+      if run.filter(|&f| on_board(f)).count() >= 3 {
+        straights.push(all[high]);
+      }
+    }
+
+    Ok(Self {
+      suits,
+      flush_suit,
+      pairing,
+      straights,
+    })
+  }
+  pub fn is_paired(&self) -> bool {
+    self.pairing != Pairing::Unpaired
+  }
+  pub fn is_connected(&self) -> bool {
+    !self.straights.is_empty()
+  }
+}
+
+fn hands(board: &[Card]) -> impl Iterator<Item = (Combo, Rank)> + '_ {
+  Combo::all()
+    .into_iter()
+    .filter(|combo| !combo.is_blocked(board))
+    .map(|combo| {
+      let deal = Deal::partial(combo.cards(), board).unwrap();
+      (combo, deal.rank())
+    })
+}
+
+// Every two card holding on a board, best first.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Strength {
+  holdings: Vec<(Combo, Rank)>,
+}
+
+impl Strength {
+  pub fn new(board: &[Card]) -> Result<Self, &'static str> {
+    validate(board)?;
+    let mut holdings: Vec<(Combo, Rank)> = hands(board).collect();
+    holdings.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    Ok(Self { holdings })
+  }
+  pub fn holdings(&self) -> &[(Combo, Rank)] {
+    &self.holdings
+  }
+  // the best holdings, all of the same rank
+  pub fn nuts(&self) -> &[(Combo, Rank)] {
+    let best = self.holdings[0].1;
+    let end = self.holdings.partition_point(|(_, rank)| *rank == best);
+    &self.holdings[..end]
+  }
+  pub fn rank(&self, combo: &Combo) -> Option<Rank> {
+    self
+      .holdings
+      .iter()
+      .find(|(c, _)| c == combo)
+      .map(|(_, rank)| *rank)
+  }
+  // how many holdings are strictly better, whatever the combo blocks
+  pub fn beaten_by(&self, combo: &Combo) -> Option<usize> {
+    let rank = self.rank(combo)?;
+    Some(self.holdings.partition_point(|(_, r)| *r > rank))
+  }
+  // The share of the other holdings this one beats, ties counting half, so
+  // the nuts alone scores 1 and the worst holding alone 0.
+  pub fn percentile(&self, combo: &Combo) -> Option<f64> {
+    let rank = self.rank(combo)?;
+    let better = self.holdings.partition_point(|(_, r)| *r > rank);
+    let worse = self.holdings.len() - self.holdings.partition_point(|(_, r)| *r >= rank);
+    let tied = self.holdings.len() - better - worse - 1;
+    Some((worse as f64 + tied as f64 / 2.0) / (self.holdings.len() - 1) as f64)
+  }
+}
+
+// The best hand anyone could hold right now, and the combos that hold it.
+pub fn nuts(board: &[Card]) -> Result<(Rank, Vec<Combo>), &'static str> {
+  let strength = Strength::new(board)?;
+  let nuts = strength.nuts();
+  let mut combos: Vec<Combo> = nuts.iter().map(|(combo, _)| *combo).collect();
+  combos.sort();
+  Ok((nuts[0].1, combos))
+}
+
+// How many combos the hand loses to, leaving out those it blocks.
+pub fn beaten_by(hole: [Card; 2], board: &[Card]) -> Result<usize, &'static str> {
+  validate(board)?;
+  let hero = Deal::partial(hole, board)?.rank();
+  Ok(
+    hands(board)
+      .filter(|(combo, rank)| !combo.is_blocked(&hole) && *rank > hero)
+      .count(),
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn cards(s: &str) -> Vec<Card> {
+    s.split_whitespace().map(|c| c.parse().unwrap()).collect()
+  }
+
+  #[test]
+  fn test_suits() {
+    let texture = |s: &str| Texture::new(&cards(s)).unwrap();
+    assert_eq!(texture("AH 7D 2C").suits, Suits::Rainbow);
+    assert_eq!(texture("AH 7H 2C").suits, Suits::TwoTone);
+    assert_eq!(texture("AH 7H 2C 3D").flush_suit, None);
+    let monotone = texture("AH 7H 2C 3H");
+    assert_eq!(monotone.suits, Suits::Monotone);
+    assert_eq!(monotone.flush_suit, Some(Suit::Hearts));
+  }
+  #[test]
+  fn test_pairing() {
+    let pairing = |s: &str| Texture::new(&cards(s)).unwrap().pairing;
+    assert_eq!(pairing("AH 7D 2C"), Pairing::Unpaired);
+    assert_eq!(pairing("AH 7D 7C"), Pairing::Paired);
+    assert_eq!(pairing("AH 7D 7C AS"), Pairing::TwoPair);
+    assert_eq!(pairing("7H 7D 7C"), Pairing::Trips);
+    assert_eq!(pairing("7H 7D 7C AS AD"), Pairing::FullHouse);
+    assert_eq!(pairing("7H 7D 7C 7S"), Pairing::Quads);
+  }
+  #[test]
+  fn test_straights() {
+    let straights = |s: &str| Texture::new(&cards(s)).unwrap().straights;
+    assert_eq!(straights("KH 7D 2C"), vec![]);
+    assert_eq!(
+      straights("9H 8D 7C"),
+      vec![Face::Jack, Face::Ten, Face::Nine]
+    );
+    assert_eq!(straights("AH 3D 5C"), vec![Face::Five]);
+    assert_eq!(straights("AH KD QC"), vec![Face::Ace]);
+    assert!(!Texture::new(&cards("KH 7D 2C")).unwrap().is_connected());
+  }
+  #[test]
+  fn test_nuts() {
+    // only the ace-king of spades makes a royal flush
+    let (rank, combos) = nuts(&cards("QS JS TS 4D 2C")).unwrap();
+    assert_eq!(rank, Rank::RoyalFlush);
+    assert_eq!(combos, vec!["ASKS".parse().unwrap()]);
+    // no straight or flush is possible, so top set is the nuts
+    let (rank, combos) = nuts(&cards("9H 8D 2C")).unwrap();
+    assert_eq!(
+      rank,
+      Rank::ThreeOfAKind(Face::Nine, [Face::Eight, Face::Two])
+    );
+    assert_eq!(combos.len(), 3);
+  }
+  #[test]
+  fn test_beaten_by() {
+    let board = cards("KS 9H 4D 2C 7S");
+    assert_eq!(
+      beaten_by(["KH", "KD"].map(|c| c.parse().unwrap()), &board),
+      Ok(0)
+    );
+    // top set is the nuts here, while top pair loses to sets, two pairs and
+    // better kickers
+    let top_pair = beaten_by(["KH", "QD"].map(|c| c.parse().unwrap()), &board).unwrap();
+    assert!(top_pair > 20);
+    assert!(beaten_by(["KH", "KD"].map(|c| c.parse().unwrap()), &board[..2]).is_err());
+  }
+  #[test]
+  fn test_strength() {
+    let board = cards("KS 9H 4D 2C 7S");
+    let strength = Strength::new(&board).unwrap();
+    assert_eq!(strength.holdings().len(), 47 * 46 / 2);
+    let combo = |s: &str| s.parse::<Combo>().unwrap();
+    assert_eq!(strength.nuts().len(), 3);
+    assert_eq!(
+      strength.percentile(&combo("KHKD")),
+      Some(1.0 - 1.0 / 1080.0)
+    );
+    assert_eq!(strength.beaten_by(&combo("KHKD")), Some(0));
+    assert_eq!(strength.beaten_by(&combo("9S9C")), Some(3));
+    // the worst hand is 5-3 high, sixteen ways
+    assert_eq!(strength.percentile(&combo("5H3D")), Some(7.5 / 1080.0));
+    assert!(strength.percentile(&combo("KHQD")).unwrap() > 0.85);
+    assert_eq!(strength.rank(&combo("KSQD")), None);
+  }
+}